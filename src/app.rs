@@ -1,23 +1,67 @@
+use crate::backend::BackendKind;
 use crate::competitive_companion::SharedProblemStore;
 use crate::judge::Judge;
-use crate::models::{Language, TestCase, TestStatus};
+use crate::models::{CheckerMode, Language, Problem, RunHistoryEntry, RunStats, TestCase, TestStatus};
+use crate::search_index::{HashingEmbedder, SearchIndex};
 use crate::storage::ProblemStore;
+use crate::ui::appearance::Appearance;
+use crate::ui::toolbar::DEFAULT_WATCH_PATTERNS;
 use crate::ui::{TestPanel, Toolbar};
 use eframe::egui;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 use uuid::Uuid;
 
 /// 应用消息
 #[derive(Debug, Clone)]
 enum AppMessage {
     ProblemsUpdated(Vec<ProblemData>),
-    CurrentProblemChanged(Option<Uuid>, Vec<TestCase>),
+    CurrentProblemChanged(Option<Uuid>, Vec<TestCase>, CheckerMode, String, String),
     #[allow(dead_code)]
     TestsUpdated(Vec<TestCase>),
     SourceFileTestsUpdated(Vec<TestCase>), // 源文件测试点更新
+    TestResult(Uuid, TestCase), // 并行执行中单个测试完成，增量回填
     RunCompleted,
+    WatchTriggered, // 监听到源文件变化，需要自动重新运行
+    StressProgress(u32, Duration), // 压力测试：已通过的迭代数、耗时
+    StressFound(TestCase),         // 压力测试找到了反例
+    StressFinished(Option<String>), // 压力测试结束（None = 未找到反例；Some = 中止原因）
+    RunReport(RunStats),             // 一次批量运行结束后的汇总统计
+    RunHistoryUpdated(Vec<RunHistoryEntry>), // 源文件判题历史（按时间正序，最近一次在末尾；每次运行落盘后刷新）
+    ProblemRunResult(Uuid, usize, usize), // 跨问题批量运行：单个问题判完（id、通过数、总数）
+    BatchRunCompleted(BatchRunSummary),   // 跨问题批量运行全部结束后的汇总
+    SearchResults(Vec<(Uuid, String, f32)>), // 语义搜索结果（id、题目名、相似度）
+}
+
+/// 将逗号分隔的 glob 模式字符串编译为 GlobSet
+fn compile_watch_patterns(patterns: &str) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    let patterns = if patterns.trim().is_empty() {
+        DEFAULT_WATCH_PATTERNS
+    } else {
+        patterns
+    };
+
+    for pattern in patterns.split(',') {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            continue;
+        }
+        if let Ok(glob) = Glob::new(pattern) {
+            builder.add(glob);
+        } else {
+            tracing::warn!("忽略无效的 watch 模式: {}", pattern);
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
 }
 
 /// 问题数据（可跨线程传递）
@@ -31,6 +75,15 @@ struct ProblemData {
     time_limit: u64,
     memory_limit: u64,
     passed: usize,
+    source_file: Option<String>,
+}
+
+/// "Run All Problems" 批量运行的汇总结果：完全通过 vs. 存在失败的问题
+#[derive(Debug, Clone, Default)]
+struct BatchRunSummary {
+    total_problems: usize,
+    fully_accepted: usize,
+    failing: Vec<(String, usize, usize)>, // (问题名, 通过数, 总数)
 }
 
 /// CPKit 主应用
@@ -40,6 +93,11 @@ pub struct CPKitApp {
 
     // UI 状态
     current_language: Language,
+    current_checker: CheckerMode,
+    previous_checker: CheckerMode, // 用于检测 checker 改变，改变时回写到当前 problem
+    time_limit_ms: u64,
+    memory_limit_mb: u64,
+    parallel_workers: usize, // 并行执行测试点时的最大并发数
     source_file: String,
     previous_source_file: String, // 用于检测源文件变化
     is_running: bool,
@@ -52,12 +110,50 @@ pub struct CPKitApp {
 
     // 运行时状态
     last_error: Option<String>,
+    last_run_stats: Option<RunStats>,
+    run_history: Vec<RunHistoryEntry>,
+    show_run_report: bool,
+    batch_summary: Option<BatchRunSummary>,
+
+    // 语义搜索
+    search_index: Arc<tokio::sync::Mutex<SearchIndex>>,
+    problem_search_query: String,
+    problem_search_results: Vec<(Uuid, String, f32)>,
 
     // 事件标志
     pending_run_all: bool,
     pending_run_test_id: Option<Uuid>, // 待运行的测试ID
     pending_stop: bool,
     pending_add_test: bool,
+    pending_run_all_problems: bool,
+
+    // Watch 模式状态
+    watch_enabled: bool,
+    watch_patterns: String,
+    watcher: Option<RecommendedWatcher>,
+    watcher_key: Option<(String, String)>, // (source_file, patterns) 上次建立 watcher 时的状态
+
+    // 问题来源后端状态
+    backend_kind: BackendKind,
+    listen_port: u16,
+    server_running: bool,
+    server_handle: Option<tokio::task::JoinHandle<()>>,
+
+    // Stress test 状态
+    stress_generator: String,
+    stress_brute: String,
+    previous_stress_generator: String, // 用于检测 generator 路径改变，改变时回写到当前 problem
+    previous_stress_brute: String,     // 用于检测 brute 路径改变，改变时回写到当前 problem
+    stress_iterations: u32,
+    stress_running: bool,
+    stress_passed: u32,
+    stress_elapsed: Duration,
+    stress_stop: Arc<AtomicBool>,
+
+    // 外观设置
+    appearance: Appearance,
+    appearance_data_dir: PathBuf,
+    show_appearance_window: bool,
 
     // 消息通道
     tx: Sender<AppMessage>,
@@ -99,10 +195,29 @@ impl CPKitApp {
             Vec::new()
         };
 
+        // 语义索引随 ProblemStore 的数据目录一起持久化；store 此时还没有被任何异步任务占用，
+        // try_lock 失败时退回当前目录也只是丢失持久化位置，不影响索引本身可用
+        let index_data_dir = problem_store
+            .try_lock()
+            .map(|store| store.data_dir().to_path_buf())
+            .unwrap_or_else(|_| PathBuf::from("."));
+        let search_index = Arc::new(tokio::sync::Mutex::new(SearchIndex::load(
+            &index_data_dir,
+            Box::new(HashingEmbedder::default()),
+        )));
+        let appearance = Appearance::load(&index_data_dir);
+
         let app = Self {
             problem_store: problem_store.clone(),
             test_panel: TestPanel::new(),
             current_language: Language::Cpp,
+            current_checker: CheckerMode::default(),
+            previous_checker: CheckerMode::default(),
+            time_limit_ms: 2000,
+            memory_limit_mb: 256,
+            parallel_workers: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
             source_file: source_file_str.clone(),
             previous_source_file: source_file_str.clone(),
             is_running: false,
@@ -111,10 +226,38 @@ impl CPKitApp {
             cached_tests: Vec::new(),
             source_file_tests,
             last_error: None,
+            last_run_stats: None,
+            run_history: Vec::new(),
+            show_run_report: false,
+            batch_summary: None,
+            search_index: search_index.clone(),
+            problem_search_query: String::new(),
+            problem_search_results: Vec::new(),
             pending_run_all: false,
             pending_run_test_id: None,
             pending_stop: false,
             pending_add_test: false,
+            pending_run_all_problems: false,
+            watch_enabled: false,
+            watch_patterns: DEFAULT_WATCH_PATTERNS.to_string(),
+            watcher: None,
+            watcher_key: None,
+            backend_kind: BackendKind::default(),
+            listen_port: crate::competitive_companion::DEFAULT_PORT,
+            server_running: false,
+            server_handle: None,
+            stress_generator: String::new(),
+            stress_brute: String::new(),
+            previous_stress_generator: String::new(),
+            previous_stress_brute: String::new(),
+            stress_iterations: 10_000,
+            stress_running: false,
+            stress_passed: 0,
+            stress_elapsed: Duration::default(),
+            stress_stop: Arc::new(AtomicBool::new(false)),
+            appearance,
+            appearance_data_dir: index_data_dir,
+            show_appearance_window: false,
             tx: tx.clone(),
             rx,
             frame_count: 0,
@@ -123,6 +266,7 @@ impl CPKitApp {
         // 启动后台任务定期同步数据
         let store = problem_store.clone();
         let tx_clone = tx.clone();
+        let search_index_bg = search_index.clone();
         tokio::spawn(async move {
             loop {
                 tokio::time::sleep(Duration::from_millis(500)).await;
@@ -147,6 +291,7 @@ impl CPKitApp {
                             time_limit: p.time_limit,
                             memory_limit: p.memory_limit,
                             passed,
+                            source_file: p.source_file.clone(),
                         }
                     })
                     .collect();
@@ -156,11 +301,49 @@ impl CPKitApp {
                     .get_current_problem()
                     .map(|p| p.tests.clone())
                     .unwrap_or_default();
+                let checker = store_lock
+                    .get_current_problem()
+                    .map(|p| p.checker.clone())
+                    .unwrap_or_default();
+                let stress_generator = store_lock
+                    .get_current_problem()
+                    .map(|p| p.stress_generator.clone())
+                    .unwrap_or_default();
+                let stress_brute = store_lock
+                    .get_current_problem()
+                    .map(|p| p.stress_brute.clone())
+                    .unwrap_or_default();
+
+                let data_dir = store_lock.data_dir().to_path_buf();
 
                 drop(store_lock);
 
+                // 为尚未建索引的新题增量生成 embedding（不阻塞主循环的其余同步）
+                {
+                    let mut index_lock = search_index_bg.lock().await;
+                    let mut changed = false;
+                    for problem in &problems {
+                        if !index_lock.contains(problem.id) {
+                            let text = format!("{} {}", problem.name, problem.group);
+                            index_lock.upsert(problem.id, &text);
+                            changed = true;
+                        }
+                    }
+                    if changed {
+                        if let Err(e) = index_lock.save(&data_dir) {
+                            tracing::warn!("保存语义索引失败: {}", e);
+                        }
+                    }
+                }
+
                 let _ = tx_clone.send(AppMessage::ProblemsUpdated(problems));
-                let _ = tx_clone.send(AppMessage::CurrentProblemChanged(current_id, tests));
+                let _ = tx_clone.send(AppMessage::CurrentProblemChanged(
+                    current_id,
+                    tests,
+                    checker,
+                    stress_generator,
+                    stress_brute,
+                ));
             }
         });
 
@@ -192,11 +375,24 @@ impl CPKitApp {
                 AppMessage::ProblemsUpdated(problems) => {
                     self.cached_problems = problems;
                 }
-                AppMessage::CurrentProblemChanged(id, tests) => {
+                AppMessage::CurrentProblemChanged(id, tests, checker, stress_generator, stress_brute) => {
                     let problem_changed = id != self.cached_current_id;
                     self.cached_current_id = id;
                     self.cached_tests = tests;
 
+                    // 切换到新 problem 时，把工具栏的 checker 同步为该题持久化的选择，
+                    // 避免沿用上一题的 checker 误判这一题
+                    if problem_changed && id.is_some() {
+                        self.current_checker = checker;
+                        self.previous_checker = self.current_checker.clone();
+
+                        // 压力测试的 generator/brute 配置同理随 problem 一起 round-trip
+                        self.stress_generator = stress_generator;
+                        self.stress_brute = stress_brute;
+                        self.previous_stress_generator = self.stress_generator.clone();
+                        self.previous_stress_brute = self.stress_brute.clone();
+                    }
+
                     // 当检测到新的 problem 时，保存测试点到源文件
                     if problem_changed && id.is_some() && !self.source_file.is_empty() {
                         tracing::info!("检测到新 problem，保存测试点到源文件");
@@ -235,13 +431,189 @@ impl CPKitApp {
                 AppMessage::SourceFileTestsUpdated(tests) => {
                     self.source_file_tests = tests;
                 }
+                AppMessage::TestResult(id, test) => {
+                    // 并行执行过程中单个测试完成，按 id 增量回填，保持面板实时刷新
+                    if let Some(slot) = self.source_file_tests.iter_mut().find(|t| t.id == id) {
+                        *slot = test.clone();
+                    }
+                    if let Some(slot) = self.cached_tests.iter_mut().find(|t| t.id == id) {
+                        *slot = test;
+                    }
+                }
                 AppMessage::RunCompleted => {
                     self.is_running = false;
                 }
+                AppMessage::WatchTriggered => {
+                    if !self.is_running {
+                        tracing::info!("检测到源文件变化，自动重新运行测试");
+                        self.pending_run_all = true;
+                    }
+                }
+                AppMessage::StressProgress(passed, elapsed) => {
+                    self.stress_passed = passed;
+                    self.stress_elapsed = elapsed;
+                }
+                AppMessage::StressFound(test) => {
+                    tracing::info!("压力测试发现反例，已追加为新的测试点");
+                    self.source_file_tests.push(test);
+                    self.save_tests_to_source_file(&self.source_file_tests);
+                    self.stress_running = false;
+                }
+                AppMessage::StressFinished(reason) => {
+                    self.stress_running = false;
+                    if let Some(reason) = reason {
+                        self.last_error = Some(reason);
+                    }
+                }
+                AppMessage::RunReport(stats) => {
+                    self.last_run_stats = Some(stats);
+                }
+                AppMessage::RunHistoryUpdated(history) => {
+                    self.run_history = history;
+                }
+                AppMessage::ProblemRunResult(id, passed, total) => {
+                    if let Some(data) = self.cached_problems.iter_mut().find(|p| p.id == id) {
+                        data.passed = passed;
+                        data.tests_len = total;
+                    }
+                }
+                AppMessage::BatchRunCompleted(summary) => {
+                    self.batch_summary = Some(summary);
+                }
+                AppMessage::SearchResults(results) => {
+                    self.problem_search_results = results;
+                }
             }
         }
     }
 
+    /// 根据 `watch_enabled`/`source_file`/`watch_patterns` 的当前状态，
+    /// 建立或拆除文件系统监听器
+    fn sync_watcher(&mut self) {
+        if !self.watch_enabled || self.source_file.is_empty() {
+            self.watcher = None;
+            self.watcher_key = None;
+            return;
+        }
+
+        let key = (self.source_file.clone(), self.watch_patterns.clone());
+        if self.watcher_key.as_ref() == Some(&key) {
+            return; // 已经在监听相同的源文件和模式
+        }
+
+        let source_path = PathBuf::from(&self.source_file);
+        let watch_dir = source_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let globset = compile_watch_patterns(&self.watch_patterns);
+        // 源文件本身总是被监听，不管其扩展名是否落在 watch_patterns 内
+        let source_file_name = source_path.file_name().map(|n| n.to_os_string());
+        let tx = self.tx.clone();
+        let mut last_trigger: Option<std::time::Instant> = None;
+        const DEBOUNCE: Duration = Duration::from_millis(300);
+
+        let watcher_result = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::warn!("文件监听事件错误: {}", e);
+                    return;
+                }
+            };
+
+            if !event.kind.is_modify() && !event.kind.is_create() {
+                return;
+            }
+
+            let matches = event.paths.iter().filter_map(|p| p.file_name()).any(|name| {
+                globset.is_match(name) || source_file_name.as_deref() == Some(name)
+            });
+
+            if !matches {
+                return;
+            }
+
+            // 去抖：~300ms 内的多次事件合并为一次
+            let now = std::time::Instant::now();
+            if let Some(last) = last_trigger {
+                if now.duration_since(last) < DEBOUNCE {
+                    return;
+                }
+            }
+            last_trigger = Some(now);
+
+            let _ = tx.send(AppMessage::WatchTriggered);
+        });
+
+        match watcher_result {
+            Ok(mut watcher) => {
+                if let Err(e) = watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
+                    tracing::warn!("无法监听目录 {:?}: {}", watch_dir, e);
+                    return;
+                }
+                tracing::info!("开始监听源文件目录: {:?}", watch_dir);
+                self.watcher = Some(watcher);
+                self.watcher_key = Some(key);
+            }
+            Err(e) => {
+                tracing::warn!("创建文件监听器失败: {}", e);
+            }
+        }
+    }
+
+    /// 启动或停止 `backend-net` 的 Competitive Companion 监听服务器
+    #[cfg(feature = "backend-net")]
+    fn toggle_server(&mut self) {
+        if self.server_running {
+            if let Some(handle) = self.server_handle.take() {
+                handle.abort();
+            }
+            self.server_running = false;
+            return;
+        }
+
+        let store = self.problem_store.clone();
+        let port = self.listen_port;
+        self.server_handle = Some(tokio::spawn(async move {
+            if let Err(e) = crate::competitive_companion::start_server(store, port).await {
+                tracing::error!("Competitive Companion 服务器启动失败: {}", e);
+            }
+        }));
+        self.server_running = true;
+    }
+
+    #[cfg(not(feature = "backend-net"))]
+    fn toggle_server(&mut self) {
+        self.last_error = Some("backend-net 未编译，无法启动监听服务器".to_string());
+    }
+
+    /// 从目录导入 `backend-fs` 风格的 `input*`/`output*` 测试文件
+    #[cfg(feature = "backend-fs")]
+    fn import_tests_from_dir(&mut self) {
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        match crate::backend::load_tests_from_dir(&dir) {
+            Ok(tests) => {
+                tracing::info!("从目录 {:?} 导入 {} 个测试点", dir, tests.len());
+                self.source_file_tests = tests;
+                self.save_tests_to_source_file(&self.source_file_tests);
+            }
+            Err(e) => {
+                self.last_error = Some(format!("导入测试点失败: {}", e));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "backend-fs"))]
+    fn import_tests_from_dir(&mut self) {
+        self.last_error = Some("backend-fs 未编译，无法从目录导入测试点".to_string());
+    }
+
     /// 运行所有测试
     fn run_all_tests(&mut self, ctx: egui::Context) {
         if self.is_running {
@@ -257,6 +629,10 @@ impl CPKitApp {
         }
 
         let language = self.current_language;
+        let checker = self.current_checker.clone();
+        let time_limit_ms = self.time_limit_ms;
+        let memory_limit_mb = self.memory_limit_mb;
+        let parallel_workers = self.parallel_workers.max(1);
         let store = self.problem_store.clone();
         let tx = self.tx.clone();
 
@@ -273,45 +649,125 @@ impl CPKitApp {
 
         // 在异步任务中运行测试
         tokio::spawn(async move {
+            let run_start = Instant::now();
             if use_source_file_tests {
-                // 使用源文件测试
+                // 使用源文件测试：先编译一次，再把每个测试点派发到一个 task，
+                // 由 Semaphore 限制并发数，所有 task 共享同一份编译产物
+                // （分别编译会在同一个 source_file.with_extension("exe") 上产生竞态）
                 if let Some(mut tests) = source_file_tests {
-                    let time_limit = Duration::from_millis(2000); // 默认时间限制
-
-                    // 重置所有测试状态
+                    let time_limit = Duration::from_millis(time_limit_ms);
+                    let original_order: Vec<Uuid> = tests.iter().map(|t| t.id).collect();
                     for test in tests.iter_mut() {
                         test.reset();
                     }
 
-                    match Judge::new() {
-                        Ok(judge) => {
-                            // 逐个运行测试
-                            for test in tests.iter_mut() {
-                                if let Err(e) = judge
-                                    .judge_test(&source_path, language, test, time_limit)
-                                    .await
-                                {
-                                    tracing::error!("测试执行失败: {}", e);
-                                    test.status = TestStatus::RuntimeError;
-                                    test.error_message = Some(format!("执行错误: {}", e));
+                    let finished: Vec<TestCase> = match Judge::new() {
+                        Ok(mut judge) => {
+                            if let Err(e) = judge.compile_once(&source_path, language, None) {
+                                tracing::error!("编译失败: {}", e);
+                                for test in tests.iter_mut() {
+                                    test.status = TestStatus::CompilationError;
+                                    test.error_message =
+                                        Some(format!("Compilation failed: {}", e));
+                                }
+                                tests
+                            } else {
+                                let judge = Arc::new(judge);
+                                let semaphore = Arc::new(Semaphore::new(parallel_workers));
+
+                                let handles: Vec<_> = tests
+                                    .drain(..)
+                                    .map(|mut test| {
+                                        let semaphore = semaphore.clone();
+                                        let judge = judge.clone();
+                                        let checker = checker.clone();
+                                        let tx = tx.clone();
+                                        let ctx = ctx.clone();
+                                        tokio::spawn(async move {
+                                            let _permit = semaphore.acquire_owned().await;
+                                            // 测试点自带的 checker 覆盖优先于运行时选择的全局 checker
+                                            let effective_checker = test
+                                                .checker
+                                                .clone()
+                                                .unwrap_or_else(|| checker.clone());
+                                            if let Err(e) = judge
+                                                .run_test(
+                                                    &mut test,
+                                                    language,
+                                                    time_limit,
+                                                    Some(memory_limit_mb),
+                                                    &effective_checker,
+                                                    None,
+                                                )
+                                                .await
+                                            {
+                                                tracing::error!("测试执行失败: {}", e);
+                                                test.status = TestStatus::RuntimeError;
+                                                test.error_message =
+                                                    Some(format!("执行错误: {}", e));
+                                            }
+
+                                            // 流式回填，使面板随结果陆续到达而增量刷新
+                                            let _ = tx
+                                                .send(AppMessage::TestResult(test.id, test.clone()));
+                                            ctx.request_repaint();
+                                            test
+                                        })
+                                    })
+                                    .collect();
+
+                                // 等待所有 permit 耗尽、全部测试完成后再汇总
+                                let mut by_id: std::collections::HashMap<Uuid, TestCase> =
+                                    std::collections::HashMap::with_capacity(handles.len());
+                                for handle in handles {
+                                    if let Ok(test) = handle.await {
+                                        by_id.insert(test.id, test);
+                                    }
                                 }
 
-                                ctx.request_repaint();
-                            }
-
-                            // 每次测试完成后发送更新
-                            let _ = tx.send(AppMessage::SourceFileTestsUpdated(tests.clone()));
+                                // 所有任务都已结束才清理编译产物，避免还在执行的任务
+                                // 读取到已被删除的可执行文件
+                                match Arc::try_unwrap(judge) {
+                                    Ok(mut judge) => judge.cleanup(),
+                                    Err(_) => {
+                                        tracing::error!("判断器仍有未完成的引用，跳过清理编译产物")
+                                    }
+                                }
 
-                            // 保存更新后的测试到源文件
-                            if let Err(e) =
-                                ProblemStore::save_tests_to_source_file(&source_path, &tests)
-                            {
-                                tracing::error!("保存测试结果失败: {}", e);
+                                original_order
+                                    .into_iter()
+                                    .filter_map(|id| by_id.remove(&id))
+                                    .collect()
                             }
                         }
                         Err(e) => {
                             tracing::error!("初始化判断器失败: {}", e);
+                            tests
+                        }
+                    };
+
+                    let _ = tx.send(AppMessage::SourceFileTestsUpdated(finished.clone()));
+                    let run_stats = RunStats::from_tests(&finished, run_start.elapsed());
+                    let _ = tx.send(AppMessage::RunReport(run_stats.clone()));
+
+                    // 保存更新后的测试到源文件
+                    if let Err(e) =
+                        ProblemStore::save_tests_to_source_file(&source_path, &finished)
+                    {
+                        tracing::error!("保存测试结果失败: {}", e);
+                    }
+
+                    // 把本次运行追加进判题历史，再把最新历史推回 GUI 展示
+                    if let Err(e) =
+                        ProblemStore::record_run(&source_path, &run_stats, language.display_name())
+                    {
+                        tracing::error!("保存判题历史失败: {}", e);
+                    }
+                    match ProblemStore::load_run_history(&source_path) {
+                        Ok(history) => {
+                            let _ = tx.send(AppMessage::RunHistoryUpdated(history));
                         }
+                        Err(e) => tracing::error!("加载判题历史失败: {}", e),
                     }
                 }
             } else {
@@ -320,34 +776,41 @@ impl CPKitApp {
 
                 if let Some(problem) = store_lock.get_current_problem_mut() {
                     let time_limit = Duration::from_millis(problem.time_limit);
-
-                    // 重置所有测试状态
-                    for test in problem.tests.iter_mut() {
-                        test.reset();
-                    }
+                    let default_checker = problem.checker.clone();
+                    let memory_limit_mb = problem.memory_limit;
 
                     match Judge::new() {
-                        Ok(judge) => {
-                            // 逐个运行测试
-                            for test in problem.tests.iter_mut() {
-                                if let Err(e) = judge
-                                    .judge_test(&source_path, language, test, time_limit)
-                                    .await
-                                {
-                                    tracing::error!("测试执行失败: {}", e);
-                                    test.status = TestStatus::RuntimeError;
-                                    test.error_message = Some(format!("执行错误: {}", e));
-                                }
-
-                                // 触发 UI 更新
-                                ctx.request_repaint();
+                        Ok(mut judge) => {
+                            // 只编译一次，再把所有测试点派发到限流的并发任务池里跑，
+                            // 避免每个测试各自重新编译/清理同一个可执行文件产生竞态
+                            if let Err(e) = judge
+                                .judge_all_tests(
+                                    &source_path,
+                                    language,
+                                    &mut problem.tests,
+                                    time_limit,
+                                    Some(memory_limit_mb),
+                                    &default_checker,
+                                    None,
+                                    parallel_workers,
+                                )
+                                .await
+                            {
+                                tracing::error!("测试执行失败: {}", e);
                             }
 
+                            // 触发 UI 更新
+                            ctx.request_repaint();
+
                             // 保存更新后的问题
                             let _ = store_lock.update_current_problem();
 
                             // 同时保存到源文件
                             if let Some(problem) = store_lock.get_current_problem() {
+                                let run_stats =
+                                    RunStats::from_tests(&problem.tests, run_start.elapsed());
+                                let _ = tx.send(AppMessage::RunReport(run_stats.clone()));
+
                                 if let Err(e) = ProblemStore::save_tests_to_source_file(
                                     &source_path,
                                     &problem.tests,
@@ -356,6 +819,21 @@ impl CPKitApp {
                                 } else {
                                     tracing::info!("已保存测试结果到源文件");
                                 }
+
+                                // 把本次运行追加进判题历史，再把最新历史推回 GUI 展示
+                                if let Err(e) = ProblemStore::record_run(
+                                    &source_path,
+                                    &run_stats,
+                                    language.display_name(),
+                                ) {
+                                    tracing::error!("保存判题历史失败: {}", e);
+                                }
+                                match ProblemStore::load_run_history(&source_path) {
+                                    Ok(history) => {
+                                        let _ = tx.send(AppMessage::RunHistoryUpdated(history));
+                                    }
+                                    Err(e) => tracing::error!("加载判题历史失败: {}", e),
+                                }
                             }
                         }
                         Err(e) => {
@@ -370,6 +848,165 @@ impl CPKitApp {
         });
     }
 
+    /// 将存储中的所有问题作为一次回归跑批：每个问题一个 task，
+    /// 由 Semaphore 限制并发编译/执行的问题数，避免无界地派生进程；
+    /// 没有 `source_file` 的问题不参与运行，也不计入汇总
+    fn run_all_problems(&mut self, ctx: egui::Context) {
+        if self.is_running {
+            return;
+        }
+
+        let store = self.problem_store.clone();
+        let tx = self.tx.clone();
+        let parallel_workers = self.parallel_workers.max(1);
+
+        self.is_running = true;
+        self.last_error = None;
+        self.batch_summary = None;
+
+        tokio::spawn(async move {
+            let problems: Vec<Problem> = {
+                let store_lock = store.lock().await;
+                store_lock.get_all_problems().into_iter().cloned().collect()
+            };
+
+            let semaphore = Arc::new(Semaphore::new(parallel_workers));
+            let handles: Vec<_> = problems
+                .into_iter()
+                .filter(|p| p.source_file.is_some())
+                .map(|mut problem| {
+                    let semaphore = semaphore.clone();
+                    let tx = tx.clone();
+                    let ctx = ctx.clone();
+                    tokio::spawn(async move {
+                        let _permit = semaphore.acquire_owned().await;
+
+                        let source_path = PathBuf::from(problem.source_file.as_deref().unwrap_or(""));
+                        let time_limit = Duration::from_millis(problem.time_limit);
+                        let default_checker = problem.checker.clone();
+
+                        for test in problem.tests.iter_mut() {
+                            test.reset();
+                            let effective_checker =
+                                test.checker.clone().unwrap_or_else(|| default_checker.clone());
+
+                            match Judge::new() {
+                                Ok(judge) => {
+                                    if let Err(e) = judge
+                                        .judge_test(
+                                            &source_path,
+                                            problem.language,
+                                            test,
+                                            time_limit,
+                                            Some(problem.memory_limit),
+                                            &effective_checker,
+                                            None,
+                                        )
+                                        .await
+                                    {
+                                        tracing::error!("批量运行问题 {} 失败: {}", problem.name, e);
+                                        test.status = TestStatus::RuntimeError;
+                                        test.error_message = Some(format!("执行错误: {}", e));
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::error!("初始化判断器失败: {}", e);
+                                }
+                            }
+                        }
+
+                        let passed = problem
+                            .tests
+                            .iter()
+                            .filter(|t| t.status == TestStatus::Accepted)
+                            .count();
+                        let _ = tx.send(AppMessage::ProblemRunResult(
+                            problem.id,
+                            passed,
+                            problem.tests.len(),
+                        ));
+                        ctx.request_repaint();
+
+                        problem
+                    })
+                })
+                .collect();
+
+            let mut finished = Vec::with_capacity(handles.len());
+            for handle in handles {
+                if let Ok(problem) = handle.await {
+                    finished.push(problem);
+                }
+            }
+
+            // 汇总通过/失败情况，并将结果写回存储
+            let mut summary = BatchRunSummary {
+                total_problems: finished.len(),
+                ..Default::default()
+            };
+
+            {
+                let mut store_lock = store.lock().await;
+                for problem in &finished {
+                    let total = problem.tests.len();
+                    let passed = problem
+                        .tests
+                        .iter()
+                        .filter(|t| t.status == TestStatus::Accepted)
+                        .count();
+
+                    if total > 0 && passed == total {
+                        summary.fully_accepted += 1;
+                    } else {
+                        summary.failing.push((problem.name.clone(), passed, total));
+                    }
+
+                    if let Some(slot) = store_lock.get_problem_mut(problem.id) {
+                        slot.tests = problem.tests.clone();
+                    }
+                    let _ = store_lock.save_problem(problem);
+                }
+            }
+
+            let _ = tx.send(AppMessage::BatchRunCompleted(summary));
+            let _ = tx.send(AppMessage::RunCompleted);
+            ctx.request_repaint();
+        });
+    }
+
+    /// 在存量问题里做一次语义搜索，结果通过 `AppMessage::SearchResults` 异步回填
+    fn run_problem_search(&mut self, ctx: egui::Context) {
+        let query = self.problem_search_query.trim().to_string();
+        if query.is_empty() {
+            self.problem_search_results.clear();
+            return;
+        }
+
+        let index = self.search_index.clone();
+        let store = self.problem_store.clone();
+        let tx = self.tx.clone();
+
+        tokio::spawn(async move {
+            let matches = index.lock().await.search(&query, 10);
+
+            let store_lock = store.lock().await;
+            let results: Vec<(Uuid, String, f32)> = matches
+                .into_iter()
+                .filter_map(|(id, score)| {
+                    store_lock
+                        .get_all_problems()
+                        .into_iter()
+                        .find(|p| p.id == id)
+                        .map(|p| (id, p.name.clone(), score))
+                })
+                .collect();
+            drop(store_lock);
+
+            let _ = tx.send(AppMessage::SearchResults(results));
+            ctx.request_repaint();
+        });
+    }
+
     /// 运行单个测试
     fn run_single_test(&mut self, ctx: egui::Context, test_id: Uuid) {
         if self.is_running {
@@ -385,6 +1022,9 @@ impl CPKitApp {
         }
 
         let language = self.current_language;
+        let checker = self.current_checker.clone();
+        let time_limit_ms = self.time_limit_ms;
+        let memory_limit_mb = self.memory_limit_mb;
         let store = self.problem_store.clone();
         let tx = self.tx.clone();
 
@@ -403,15 +1043,25 @@ impl CPKitApp {
             if use_source_file_tests {
                 // 使用源文件测试
                 if let Some(mut tests) = source_file_tests {
-                    let time_limit = Duration::from_millis(2000);
+                    let time_limit = Duration::from_millis(time_limit_ms);
 
                     if let Some(test) = tests.iter_mut().find(|t| t.id == test_id) {
                         test.reset();
+                        let effective_checker =
+                            test.checker.clone().unwrap_or_else(|| checker.clone());
 
                         match Judge::new() {
                             Ok(judge) => {
                                 if let Err(e) = judge
-                                    .judge_test(&source_path, language, test, time_limit)
+                                    .judge_test(
+                                        &source_path,
+                                        language,
+                                        test,
+                                        time_limit,
+                                        Some(memory_limit_mb),
+                                        &effective_checker,
+                                        None,
+                                    )
                                     .await
                                 {
                                     tracing::error!("测试执行失败: {}", e);
@@ -441,14 +1091,27 @@ impl CPKitApp {
 
                 if let Some(problem) = store_lock.get_current_problem_mut() {
                     let time_limit = Duration::from_millis(problem.time_limit);
+                    let default_checker = problem.checker.clone();
 
                     if let Some(test) = problem.tests.iter_mut().find(|t| t.id == test_id) {
                         test.reset();
+                        let effective_checker = test
+                            .checker
+                            .clone()
+                            .unwrap_or_else(|| default_checker.clone());
 
                         match Judge::new() {
                             Ok(judge) => {
                                 if let Err(e) = judge
-                                    .judge_test(&source_path, language, test, time_limit)
+                                    .judge_test(
+                                        &source_path,
+                                        language,
+                                        test,
+                                        time_limit,
+                                        Some(problem.memory_limit),
+                                        &effective_checker,
+                                        None,
+                                    )
                                     .await
                                 {
                                     tracing::error!("测试执行失败: {}", e);
@@ -484,6 +1147,170 @@ impl CPKitApp {
         });
     }
 
+    /// 压力测试：反复用 generator 生成输入，比较主解法与暴力解法的输出，
+    /// 在第一次不一致（或主解法崩溃/超时）时停下并把反例追加为新的测试点
+    fn run_stress_test(&mut self, ctx: egui::Context) {
+        if self.stress_running || self.is_running {
+            return;
+        }
+
+        let source_file = self.source_file.clone();
+        let source_path = PathBuf::from(&source_file);
+        if !source_path.exists() {
+            self.last_error = Some(format!("源文件不存在: {}", source_file));
+            return;
+        }
+
+        if self.stress_generator.is_empty() || self.stress_brute.is_empty() {
+            self.last_error = Some("请先设置 generator 和暴力解法的程序路径".to_string());
+            return;
+        }
+
+        let generator_path = PathBuf::from(&self.stress_generator);
+        let brute_path = PathBuf::from(&self.stress_brute);
+        let language = self.current_language;
+        let checker = self.current_checker.clone();
+        let iterations = self.stress_iterations.max(1);
+        let time_limit = Duration::from_millis(self.time_limit_ms);
+        let memory_limit_mb = self.memory_limit_mb;
+        let tx = self.tx.clone();
+
+        self.stress_stop.store(false, Ordering::Relaxed);
+        let stop_flag = self.stress_stop.clone();
+
+        self.stress_running = true;
+        self.stress_passed = 0;
+        self.stress_elapsed = Duration::default();
+        self.last_error = None;
+
+        tokio::spawn(async move {
+            let start = Instant::now();
+
+            let mut main_judge = match Judge::new() {
+                Ok(j) => j,
+                Err(e) => {
+                    let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                        "初始化判断器失败: {}",
+                        e
+                    ))));
+                    return;
+                }
+            };
+            if let Err(e) = main_judge.compile_once(&source_path, language, None) {
+                let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                    "主程序编译失败: {}",
+                    e
+                ))));
+                return;
+            }
+
+            let mut brute_judge = match Judge::new() {
+                Ok(j) => j,
+                Err(e) => {
+                    let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                        "初始化判断器失败: {}",
+                        e
+                    ))));
+                    return;
+                }
+            };
+            if let Err(e) = brute_judge.compile_once(&brute_path, language, None) {
+                let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                    "暴力解法编译失败: {}",
+                    e
+                ))));
+                return;
+            }
+
+            for seed in 1..=iterations {
+                if stop_flag.load(Ordering::Relaxed) {
+                    let _ = tx.send(AppMessage::StressFinished(None));
+                    return;
+                }
+
+                let input = match Command::new(&generator_path).arg(seed.to_string()).output() {
+                    Ok(output) if output.status.success() => {
+                        String::from_utf8_lossy(&output.stdout).to_string()
+                    }
+                    Ok(output) => {
+                        let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                            "generator 在第 {} 次迭代异常退出: {}",
+                            seed,
+                            String::from_utf8_lossy(&output.stderr)
+                        ))));
+                        return;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                            "无法运行 generator: {}",
+                            e
+                        ))));
+                        return;
+                    }
+                };
+
+                // 暴力解法的输出作为本次迭代的期望输出
+                let mut brute_test = TestCase::new(input.clone(), String::new());
+                if let Err(e) = brute_judge
+                    .run_test(
+                        &mut brute_test,
+                        language,
+                        time_limit,
+                        Some(memory_limit_mb),
+                        &CheckerMode::Exact,
+                        None,
+                    )
+                    .await
+                {
+                    let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                        "暴力解法执行失败: {}",
+                        e
+                    ))));
+                    return;
+                }
+                if !matches!(brute_test.status, TestStatus::Accepted | TestStatus::WrongAnswer) {
+                    let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                        "暴力解法在第 {} 次迭代 {}",
+                        seed,
+                        brute_test.status.text()
+                    ))));
+                    return;
+                }
+                let expected_output = brute_test.actual_output.unwrap_or_default();
+
+                let mut main_test = TestCase::new(input, expected_output);
+                if let Err(e) = main_judge
+                    .run_test(
+                        &mut main_test,
+                        language,
+                        time_limit,
+                        Some(memory_limit_mb),
+                        &checker,
+                        None,
+                    )
+                    .await
+                {
+                    let _ = tx.send(AppMessage::StressFinished(Some(format!(
+                        "主程序执行失败: {}",
+                        e
+                    ))));
+                    return;
+                }
+
+                if main_test.status != TestStatus::Accepted {
+                    let _ = tx.send(AppMessage::StressFound(main_test));
+                    return;
+                }
+
+                let _ = tx.send(AppMessage::StressProgress(seed, start.elapsed()));
+                ctx.request_repaint();
+            }
+
+            let _ = tx.send(AppMessage::StressFinished(None));
+            ctx.request_repaint();
+        });
+    }
+
     /// 检查源文件是否改变，如果改变则加载对应的测试点
     fn check_source_file_changed(&mut self) {
         if self.source_file != self.previous_source_file {
@@ -504,6 +1331,51 @@ impl CPKitApp {
         }
     }
 
+    /// 检查工具栏的 checker 选择是否改变，改变时持久化到当前 problem
+    /// （使 checker 选择跟随 problem 一起保存，随 Competitive Companion 导入的数据一起round-trip）
+    fn check_checker_changed(&mut self) {
+        if self.current_checker != self.previous_checker {
+            self.previous_checker = self.current_checker.clone();
+
+            if self.cached_current_id.is_some() {
+                let store = self.problem_store.clone();
+                let checker = self.current_checker.clone();
+                tokio::spawn(async move {
+                    let mut store_lock = store.lock().await;
+                    if let Some(problem) = store_lock.get_current_problem_mut() {
+                        problem.checker = checker;
+                        let _ = store_lock.update_current_problem();
+                    }
+                });
+            }
+        }
+    }
+
+    /// 检查压力测试的 generator/brute 配置是否改变，改变时持久化到当前 problem
+    /// （与 [`Self::check_checker_changed`] 同理，使配置跟随 problem 一起保存）
+    fn check_stress_config_changed(&mut self) {
+        if self.stress_generator != self.previous_stress_generator
+            || self.stress_brute != self.previous_stress_brute
+        {
+            self.previous_stress_generator = self.stress_generator.clone();
+            self.previous_stress_brute = self.stress_brute.clone();
+
+            if self.cached_current_id.is_some() {
+                let store = self.problem_store.clone();
+                let generator = self.stress_generator.clone();
+                let brute = self.stress_brute.clone();
+                tokio::spawn(async move {
+                    let mut store_lock = store.lock().await;
+                    if let Some(problem) = store_lock.get_current_problem_mut() {
+                        problem.stress_generator = generator;
+                        problem.stress_brute = brute;
+                        let _ = store_lock.update_current_problem();
+                    }
+                });
+            }
+        }
+    }
+
     /// 保存测试点到源文件
     fn save_tests_to_source_file(&self, tests: &[TestCase]) {
         if !self.source_file.is_empty() {
@@ -527,6 +1399,10 @@ impl CPKitApp {
     fn render_ui(&mut self, ctx: &egui::Context) {
         // 检查源文件是否改变
         self.check_source_file_changed();
+        // 检查 checker 选择是否改变
+        self.check_checker_changed();
+        // 检查压力测试配置是否改变
+        self.check_stress_config_changed();
 
         // 顶部工具栏
         egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
@@ -536,6 +1412,10 @@ impl CPKitApp {
             let mut stop = false;
             let mut add_test = false;
             let mut clear_results = false;
+            let mut toggle_server = false;
+            let mut import_dir = false;
+            let mut toggle_stress = false;
+            let mut run_all_problems = false;
 
             let has_problem = self.cached_current_id.is_some();
 
@@ -547,8 +1427,27 @@ impl CPKitApp {
                 &mut stop,
                 &mut add_test,
                 &mut clear_results,
+                &mut run_all_problems,
                 has_problem,
                 self.is_running,
+                &mut self.watch_enabled,
+                &mut self.watch_patterns,
+                &mut self.current_checker,
+                &mut self.time_limit_ms,
+                &mut self.memory_limit_mb,
+                &mut self.backend_kind,
+                &mut self.listen_port,
+                self.server_running,
+                &mut toggle_server,
+                &mut import_dir,
+                &mut self.parallel_workers,
+                &mut self.stress_generator,
+                &mut self.stress_brute,
+                &mut self.stress_iterations,
+                self.stress_running,
+                self.stress_passed,
+                self.stress_elapsed.as_secs_f32(),
+                &mut toggle_stress,
             );
 
             if run_all {
@@ -560,6 +1459,22 @@ impl CPKitApp {
             if add_test {
                 self.pending_add_test = true;
             }
+            if run_all_problems {
+                self.pending_run_all_problems = true;
+            }
+            if toggle_server {
+                self.toggle_server();
+            }
+            if import_dir {
+                self.import_tests_from_dir();
+            }
+            if toggle_stress {
+                if self.stress_running {
+                    self.stress_stop.store(true, Ordering::Relaxed);
+                } else {
+                    self.run_stress_test(ctx.clone());
+                }
+            }
 
             // 处理清除结果
             if clear_results {
@@ -599,9 +1514,218 @@ impl CPKitApp {
                     );
                 });
             });
+
+            if self.last_run_stats.is_some() {
+                ui.add_space(2.0);
+                egui::CollapsingHeader::new("📊 Run Report")
+                    .default_open(self.show_run_report)
+                    .show(ui, |ui| {
+                        let stats = self.last_run_stats.clone().unwrap();
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "Total: {}  ✓ {}  ✗ {}  ⚠ {}  ⏱ {}  💾 {}  🔨 {}",
+                                stats.total,
+                                stats.accepted,
+                                stats.wrong_answer,
+                                stats.runtime_error,
+                                stats.time_limit_exceeded,
+                                stats.memory_limit_exceeded,
+                                stats.compilation_error,
+                            ));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Wall time: {:.2?}", stats.total_wall_time));
+                            if let Some(max) = stats.max_execution_time {
+                                ui.label(format!("Max: {:.2?}", max));
+                            }
+                            if let Some(mean) = stats.mean_execution_time {
+                                ui.label(format!("Mean: {:.2?}", mean));
+                            }
+                            if let Some(peak) = stats.peak_memory_kb {
+                                ui.label(format!("Peak mem: {} KB", peak));
+                            }
+                        });
+                        if let Some(slowest) = stats.slowest_test_id {
+                            ui.label(
+                                egui::RichText::new(format!("Slowest test: {}", slowest))
+                                    .size(11.0)
+                                    .color(egui::Color32::GRAY),
+                            );
+                        }
+
+                        if ui.button("💾 Export JSON").clicked() {
+                            let default_name = std::path::Path::new(&self.source_file)
+                                .file_stem()
+                                .and_then(|s| s.to_str())
+                                .map(|s| format!("{}_run_report.json", s))
+                                .unwrap_or_else(|| "run_report.json".to_string());
+
+                            if let Some(path) = rfd::FileDialog::new()
+                                .set_file_name(&default_name)
+                                .add_filter("JSON", &["json"])
+                                .save_file()
+                            {
+                                match serde_json::to_string_pretty(&stats) {
+                                    Ok(json) => {
+                                        if let Err(e) = std::fs::write(&path, json) {
+                                            self.last_error =
+                                                Some(format!("导出运行报告失败: {}", e));
+                                        }
+                                    }
+                                    Err(e) => {
+                                        self.last_error =
+                                            Some(format!("序列化运行报告失败: {}", e));
+                                    }
+                                }
+                            }
+                        }
+                    });
+            }
+
+            if !self.run_history.is_empty() {
+                ui.add_space(2.0);
+                egui::CollapsingHeader::new("📈 Run History")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        for entry in self.run_history.iter().rev().take(10) {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(
+                                        entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                                    )
+                                    .size(11.0)
+                                    .color(egui::Color32::GRAY),
+                                );
+                                ui.label(&entry.compiler);
+                                ui.label(format!(
+                                    "✓ {}/{}",
+                                    entry.stats.accepted, entry.stats.total
+                                ));
+                                ui.label(format!("⏱ {:.2?}", entry.stats.total_wall_time));
+                            });
+                        }
+                    });
+            }
+
+            if let Some(summary) = self.batch_summary.clone() {
+                ui.add_space(2.0);
+                egui::CollapsingHeader::new("🗂 Batch Run Summary")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.label(format!(
+                            "Problems: {}  ✓ fully accepted: {}  ✗ with failures: {}",
+                            summary.total_problems,
+                            summary.fully_accepted,
+                            summary.failing.len(),
+                        ));
+
+                        if !summary.failing.is_empty() {
+                            egui::Grid::new("batch_run_failing_table")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.label(egui::RichText::new("Problem").strong());
+                                    ui.label(egui::RichText::new("Passed").strong());
+                                    ui.end_row();
+
+                                    for (name, passed, total) in &summary.failing {
+                                        ui.label(name);
+                                        ui.label(format!("{}/{}", passed, total));
+                                        ui.end_row();
+                                    }
+                                });
+                        }
+                    });
+            }
             ui.add_space(2.0);
         });
 
+        // 比赛边栏：按 group 对题目分组，点击可在题目间切换
+        // （整场比赛批量导入后，用来在生成的各题 stub 之间跳转）
+        let mut groups: std::collections::BTreeMap<String, Vec<ProblemData>> =
+            std::collections::BTreeMap::new();
+        for problem in &self.cached_problems {
+            if !problem.group.is_empty() {
+                groups
+                    .entry(problem.group.clone())
+                    .or_default()
+                    .push(problem.clone());
+            }
+        }
+
+        if !groups.is_empty() || !self.cached_problems.is_empty() {
+            egui::SidePanel::left("contest_sidebar")
+                .default_width(200.0)
+                .show(ctx, |ui| {
+                    ui.heading("🏁 Contests");
+                    ui.add_space(4.0);
+
+                    let mut switch_to: Option<(Uuid, Option<String>)> = None;
+
+                    // 语义搜索：按余弦相似度在存量问题里查找
+                    ui.label(egui::RichText::new("🔍 Search").strong());
+                    let search_changed = ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.problem_search_query)
+                                .hint_text("e.g. dsu on tree"),
+                        )
+                        .changed();
+                    if search_changed {
+                        self.run_problem_search(ctx.clone());
+                    }
+
+                    if !self.problem_search_results.is_empty() {
+                        ui.add_space(4.0);
+                        for (id, name, score) in self.problem_search_results.clone() {
+                            let is_current = self.cached_current_id == Some(id);
+                            let label = format!("{}  ({:.2})", name, score);
+                            if ui.selectable_label(is_current, label).clicked() && !is_current {
+                                let source_file = self
+                                    .cached_problems
+                                    .iter()
+                                    .find(|p| p.id == id)
+                                    .and_then(|p| p.source_file.clone());
+                                switch_to = Some((id, source_file));
+                            }
+                        }
+                    }
+
+                    ui.separator();
+                    ui.add_space(4.0);
+
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for (group, problems) in &groups {
+                            egui::CollapsingHeader::new(group)
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    for problem in problems {
+                                        let is_current = self.cached_current_id == Some(problem.id);
+                                        let label = format!(
+                                            "{} ({}/{})",
+                                            problem.name, problem.passed, problem.tests_len
+                                        );
+                                        if ui.selectable_label(is_current, label).clicked()
+                                            && !is_current
+                                        {
+                                            switch_to =
+                                                Some((problem.id, problem.source_file.clone()));
+                                        }
+                                    }
+                                });
+                        }
+                    });
+
+                    if let Some((id, source_file)) = switch_to {
+                        if let Some(source_file) = source_file {
+                            self.source_file = source_file;
+                        }
+                        let store = self.problem_store.clone();
+                        tokio::spawn(async move {
+                            store.lock().await.set_current_problem(id);
+                        });
+                    }
+                });
+        }
+
         // 中央测试面板
         egui::CentralPanel::default().show(ctx, |ui| {
             // 只要有源文件就允许显示和编辑测试点
@@ -620,8 +1744,33 @@ impl CPKitApp {
                     self.cached_tests.clone()
                 };
 
-                self.test_panel
-                    .ui(ui, &mut tests, &mut on_delete_test, self.pending_add_test);
+                let mut toggle_stress_from_panel = false;
+                let mut open_appearance_window = false;
+                self.test_panel.ui(
+                    ui,
+                    &mut tests,
+                    &mut on_delete_test,
+                    self.pending_add_test,
+                    &mut self.watch_enabled,
+                    &mut self.current_checker,
+                    !self.stress_generator.is_empty() && !self.stress_brute.is_empty(),
+                    self.stress_running,
+                    self.stress_passed,
+                    self.stress_elapsed.as_secs_f32(),
+                    &mut toggle_stress_from_panel,
+                    &self.appearance,
+                    &mut open_appearance_window,
+                );
+                if toggle_stress_from_panel {
+                    if self.stress_running {
+                        self.stress_stop.store(true, Ordering::Relaxed);
+                    } else {
+                        self.run_stress_test(ctx.clone());
+                    }
+                }
+                if open_appearance_window {
+                    self.show_appearance_window = true;
+                }
 
                 self.pending_add_test = false;
 
@@ -770,6 +1919,12 @@ impl CPKitApp {
                 });
             }
         });
+
+        if self.show_appearance_window
+            && self.appearance.window(ctx, &mut self.show_appearance_window)
+        {
+            let _ = self.appearance.save(&self.appearance_data_dir);
+        }
     }
 }
 
@@ -784,6 +1939,11 @@ impl eframe::App for CPKitApp {
             self.run_all_tests(ctx.clone());
         }
 
+        if self.pending_run_all_problems {
+            self.pending_run_all_problems = false;
+            self.run_all_problems(ctx.clone());
+        }
+
         // 处理从test_panel触发的单个测试运行
         if let Some(test_id) = self.pending_run_test_id.take() {
             self.run_single_test(ctx.clone(), test_id);
@@ -797,6 +1957,9 @@ impl eframe::App for CPKitApp {
         // 渲染 UI
         self.render_ui(ctx);
 
+        // 根据最新的 watch 状态建立/拆除监听器
+        self.sync_watcher();
+
         // 如果正在运行，请求持续重绘
         if self.is_running {
             ctx.request_repaint();
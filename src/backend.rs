@@ -0,0 +1,67 @@
+use crate::models::TestCase;
+use anyhow::Result;
+use std::path::Path;
+
+/// 问题来源后端（镜像 `backend-fs`/`backend-net` 两个 Cargo feature）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    /// 从目录中的 `input*`/`output*` 文件读取测试点
+    Filesystem,
+    /// 监听 Competitive Companion 浏览器插件推送的 HTTP 请求
+    Network,
+}
+
+impl Default for BackendKind {
+    fn default() -> Self {
+        BackendKind::Network
+    }
+}
+
+impl BackendKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackendKind::Filesystem => "Filesystem",
+            BackendKind::Network => "Network",
+        }
+    }
+
+    pub fn all() -> &'static [BackendKind] {
+        &[BackendKind::Filesystem, BackendKind::Network]
+    }
+}
+
+/// 从目录读取形如 `input0`/`output0`, `input1`/`output1`, ... 的测试数据
+/// （`backend-fs` 后端，后缀相同的一对文件组成一个测试点）
+#[cfg(feature = "backend-fs")]
+pub fn load_tests_from_dir(dir: &Path) -> Result<Vec<TestCase>> {
+    let mut pairs: std::collections::BTreeMap<String, (Option<String>, Option<String>)> =
+        std::collections::BTreeMap::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(n) => n.to_string(),
+            None => continue,
+        };
+
+        if let Some(suffix) = name.strip_prefix("input") {
+            pairs.entry(suffix.to_string()).or_default().0 = Some(std::fs::read_to_string(&path)?);
+        } else if let Some(suffix) = name.strip_prefix("output") {
+            pairs.entry(suffix.to_string()).or_default().1 = Some(std::fs::read_to_string(&path)?);
+        }
+    }
+
+    let mut tests = Vec::new();
+    for (_, (input, output)) in pairs {
+        if let (Some(input), Some(output)) = (input, output) {
+            tests.push(TestCase::new(input, output));
+        }
+    }
+
+    Ok(tests)
+}
@@ -0,0 +1,22 @@
+use cpkit::app::CPKitApp;
+use cpkit::storage::ProblemStore;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// egui 版本的入口：`cargo run --bin cpkit-egui -- [source_file]`
+#[tokio::main]
+async fn main() -> eframe::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let source_file = std::env::args().nth(1);
+    let problem_store = Arc::new(Mutex::new(
+        ProblemStore::new().expect("Failed to initialize problem store"),
+    ));
+
+    let options = eframe::NativeOptions::default();
+    eframe::run_native(
+        "CPKit",
+        options,
+        Box::new(move |cc| Ok(Box::new(CPKitApp::new(cc, problem_store, source_file)))),
+    )
+}
@@ -1,17 +1,53 @@
-use crate::models::{CompetitiveCompanionData, Problem};
+use crate::models::{CompetitiveCompanionData, Language, Problem};
 use crate::storage::ProblemStore;
 use anyhow::Result;
 use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
 
-const DEFAULT_PORT: u16 = 10043;
+/// `backend-net` 后端的默认监听端口
+pub const DEFAULT_PORT: u16 = 10043;
 
 pub type SharedProblemStore = Arc<Mutex<ProblemStore>>;
 
-/// 启动 Competitive Companion 监听服务器
-pub async fn start_server(store: SharedProblemStore) -> Result<()> {
+/// 解析整场比赛时，Competitive Companion 会对每道题各发一次 POST；
+/// 同一个 `group` 的 payload 在这段静默窗口内都没有新题到达，就判定为"到齐了"并一次性落地
+#[cfg(feature = "backend-net")]
+const CONTEST_BATCH_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// 同一场比赛（按 `group` 分组）在静默窗口关闭前暂存的 payload
+#[cfg(feature = "backend-net")]
+struct ContestBuffer {
+    problems: Vec<CompetitiveCompanionData>,
+    last_received: Instant,
+}
+
+#[cfg(feature = "backend-net")]
+type ContestBatches = Arc<Mutex<HashMap<String, ContestBuffer>>>;
+
+/// axum 路由的共享状态：问题存储 + 正在缓冲的比赛批次
+#[cfg(feature = "backend-net")]
+#[derive(Clone)]
+struct AppState {
+    store: SharedProblemStore,
+    batches: ContestBatches,
+}
+
+/// 启动 Competitive Companion 监听服务器（`backend-net` 后端）
+#[cfg(feature = "backend-net")]
+pub async fn start_server(store: SharedProblemStore, port: u16) -> Result<()> {
+    let state = AppState {
+        store,
+        batches: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    // 后台定时扫描缓冲区，静默窗口过后把整场比赛一次性落地成 stub 文件
+    tokio::spawn(flush_loop(state.clone()));
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
@@ -20,9 +56,9 @@ pub async fn start_server(store: SharedProblemStore) -> Result<()> {
     let app = Router::new()
         .route("/", post(receive_problem))
         .layer(cors)
-        .with_state(store);
+        .with_state(state);
 
-    let addr = format!("127.0.0.1:{}", DEFAULT_PORT);
+    let addr = format!("127.0.0.1:{}", port);
     tracing::info!("Competitive Companion server started on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
@@ -31,24 +67,117 @@ pub async fn start_server(store: SharedProblemStore) -> Result<()> {
     Ok(())
 }
 
-/// 接收从 Competitive Companion 发送的问题数据
+/// 接收从 Competitive Companion 发送的单道题目数据，先进入对应 `group` 的缓冲区
+#[cfg(feature = "backend-net")]
 async fn receive_problem(
-    State(store): State<SharedProblemStore>,
+    State(state): State<AppState>,
     Json(data): Json<CompetitiveCompanionData>,
 ) -> impl IntoResponse {
-    tracing::info!("Received new problem: {}", data.name);
+    tracing::info!("Received problem '{}' (group: {})", data.name, data.group);
 
-    let problem: Problem = data.into();
-    let problem_name = problem.name.clone();
+    let key = if data.group.trim().is_empty() {
+        data.name.clone()
+    } else {
+        data.group.clone()
+    };
 
-    match store.lock().await.add_problem(problem) {
-        Ok(_) => {
-            tracing::info!("Problem '{}' saved", problem_name);
-            (StatusCode::OK, "Problem received")
+    let mut batches = state.batches.lock().await;
+    let buffer = batches.entry(key).or_insert_with(|| ContestBuffer {
+        problems: Vec::new(),
+        last_received: Instant::now(),
+    });
+    buffer.problems.push(data);
+    buffer.last_received = Instant::now();
+
+    (StatusCode::OK, "Problem received")
+}
+
+/// 定期扫描比赛缓冲区，把静默窗口已过的整场比赛落地
+#[cfg(feature = "backend-net")]
+async fn flush_loop(state: AppState) {
+    loop {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let ready: Vec<(String, Vec<CompetitiveCompanionData>)> = {
+            let mut batches = state.batches.lock().await;
+            let ready_keys: Vec<String> = batches
+                .iter()
+                .filter(|(_, buf)| buf.last_received.elapsed() >= CONTEST_BATCH_DEBOUNCE)
+                .map(|(key, _)| key.clone())
+                .collect();
+
+            ready_keys
+                .into_iter()
+                .filter_map(|key| batches.remove(&key).map(|buf| (key, buf.problems)))
+                .collect()
+        };
+
+        for (group, problems) in ready {
+            materialize_contest(&state.store, &group, problems).await;
         }
-        Err(e) => {
-            tracing::error!("Failed to save problem: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Save failed")
+    }
+}
+
+/// 把一场比赛缓冲到齐的题目按顺序物化：为每题生成 stub 源文件、
+/// 通过既有的 `save_tests_to_source_file` 附加测试点，再存入 `ProblemStore`
+#[cfg(feature = "backend-net")]
+async fn materialize_contest(
+    store: &SharedProblemStore,
+    group: &str,
+    payloads: Vec<CompetitiveCompanionData>,
+) {
+    if payloads.is_empty() {
+        return;
+    }
+
+    tracing::info!(
+        "比赛 '{}' 已到齐 {} 道题，开始批量生成题目文件",
+        group,
+        payloads.len()
+    );
+
+    let mut store_lock = store.lock().await;
+    let template = store_lock.stub_template().to_string();
+    let dest_dir = store_lock
+        .stub_dir()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    for (i, data) in payloads.into_iter().enumerate() {
+        let mut problem: Problem = data.into();
+
+        let stem = ProblemStore::render_stub_filename(&template, i + 1, &problem.name);
+        let filename = format!("{}.{}", stem, problem.language.file_extension());
+        let source_path = dest_dir.join(&filename);
+
+        if !source_path.exists() {
+            if let Some(parent) = source_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let _ = std::fs::write(&source_path, stub_boilerplate(problem.language));
         }
+
+        problem.source_file = Some(source_path.display().to_string());
+
+        if let Err(e) = ProblemStore::save_tests_to_source_file(&source_path, &problem.tests) {
+            tracing::error!("保存 '{}' 的测试点到源文件失败: {}", problem.name, e);
+        }
+
+        let problem_name = problem.name.clone();
+        if let Err(e) = store_lock.add_problem(problem) {
+            tracing::error!("保存题目 '{}' 失败: {}", problem_name, e);
+        }
+    }
+}
+
+/// 每种语言的最小 stub 骨架，供比赛批量导入时直接落地
+#[cfg(feature = "backend-net")]
+fn stub_boilerplate(language: Language) -> &'static str {
+    match language {
+        Language::Cpp => "#include <bits/stdc++.h>\nusing namespace std;\n\nint main() {\n    \n    return 0;\n}\n",
+        Language::C => "#include <stdio.h>\n\nint main(void) {\n    \n    return 0;\n}\n",
+        Language::Rust => "fn main() {\n    \n}\n",
+        Language::Java => "public class Main {\n    public static void main(String[] args) {\n        \n    }\n}\n",
+        Language::Python => "def main():\n    pass\n\n\nif __name__ == \"__main__\":\n    main()\n",
     }
 }
@@ -1,11 +1,14 @@
 use crate::models::{ExecutionResult, Language};
 use anyhow::{Context, Result};
+#[cfg(unix)]
+use std::io::Read;
 use std::io::Write;
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 /// 代码执行器
+#[derive(Clone)]
 pub struct Executor {
     #[allow(dead_code)]
     work_dir: std::path::PathBuf,
@@ -123,76 +126,294 @@ impl Executor {
         Ok(source_file.with_extension("class"))
     }
 
-    /// 执行程序
+    /// 执行程序，并根据 `time_limit`/`memory_limit` 分类出 TLE/MLE/RE 等 verdict
     pub fn execute(
         &self,
         executable: &Path,
         input: &str,
         language: Language,
         time_limit: Duration,
+        memory_limit_mb: Option<u64>,
     ) -> Result<ExecutionResult> {
-        let start = Instant::now();
+        // 原生编译产物走真正隔离的沙箱（fork + setrlimit + cgroup），
+        // 获得结构化的 TLE/MLE 分类和更准确的峰值内存；
+        // Python/Java 以及非 Linux 平台沿用下面不带隔离的 spawn 实现
+        #[cfg(target_os = "linux")]
+        if matches!(language, Language::Cpp | Language::Rust | Language::C) {
+            return crate::sandbox::run_guarded(
+                executable,
+                input,
+                time_limit,
+                time_limit,
+                memory_limit_mb,
+            );
+        }
+
+        // Python/Java 解释器以及非 Linux 平台上的所有语言都走这里：用
+        // pre_exec + setrlimit 在 exec 前就挂好硬限制，再用 wait4/getrusage
+        // 实地读出 ru_maxrss，不再需要轮询 /proc/<pid>/status 采样
+        #[cfg(unix)]
+        return Self::spawn_and_wait_unix(executable, input, language, time_limit, memory_limit_mb);
+
+        #[cfg(not(unix))]
+        return Self::spawn_and_wait_other(executable, input, language, time_limit, memory_limit_mb);
+    }
 
-        let mut child = match language {
-            Language::Python => Command::new("python")
-                .arg(executable)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .context("Cannot start Python interpreter")?,
+    /// 构造解释型/非 Linux 语言的启动命令（Python 走解释器，Java 走 JVM 并切到 class 所在目录）
+    fn build_command(executable: &Path, language: Language) -> Result<Command> {
+        let mut command = match language {
+            Language::Python => {
+                let mut cmd = Command::new("python");
+                cmd.arg(executable);
+                cmd
+            }
             Language::Java => {
                 let class_name = executable
                     .file_stem()
                     .and_then(|s| s.to_str())
-                    .context("Invalid Java class name")?;
-
+                    .context("Invalid Java class name")?
+                    .to_string();
                 let dir = executable.parent().context("Cannot get directory")?;
 
-                Command::new("java")
-                    .current_dir(dir)
-                    .arg(class_name)
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .spawn()
-                    .context("Cannot start Java Virtual Machine")?
+                let mut cmd = Command::new("java");
+                cmd.current_dir(dir).arg(class_name);
+                cmd
             }
-            _ => Command::new(executable)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .context("Cannot start program")?,
+            _ => Command::new(executable),
+        };
+
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        Ok(command)
+    }
+
+    /// Unix 专用执行路径：exec 前用 `pre_exec` 挂 `setrlimit(RLIMIT_CPU)`（以及非 JVM 场景下的
+    /// `RLIMIT_AS`——JVM 自身会预留远超实际堆大小的虚拟地址空间，硬挂 RLIMIT_AS 会直接打不开），
+    /// 结束后用 `wait4` 一次性拿到退出状态和 `getrusage` 的 `ru_maxrss`（Linux 上已经是 KB）
+    #[cfg(unix)]
+    fn spawn_and_wait_unix(
+        executable: &Path,
+        input: &str,
+        language: Language,
+        time_limit: Duration,
+        memory_limit_mb: Option<u64>,
+    ) -> Result<ExecutionResult> {
+        use std::os::unix::process::CommandExt;
+
+        let cpu_secs = time_limit.as_secs().max(1);
+        let mem_bytes = if !matches!(language, Language::Java) {
+            memory_limit_mb.map(|mb| mb * 1024 * 1024)
+        } else {
+            None
         };
 
-        // 写入输入
+        let mut command = Self::build_command(executable, language)?;
+        unsafe {
+            command.pre_exec(move || {
+                let cpu_limit = libc::rlimit {
+                    rlim_cur: cpu_secs,
+                    rlim_max: cpu_secs,
+                };
+                libc::setrlimit(libc::RLIMIT_CPU, &cpu_limit);
+
+                if let Some(bytes) = mem_bytes {
+                    let as_limit = libc::rlimit {
+                        rlim_cur: bytes,
+                        rlim_max: bytes,
+                    };
+                    libc::setrlimit(libc::RLIMIT_AS, &as_limit);
+                }
+
+                Ok(())
+            });
+        }
+
+        let start = Instant::now();
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Cannot start program: {:?}", executable))?;
+
+        let pid = child.id() as libc::pid_t;
+
         if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(input.as_bytes())?;
+            // 子进程可能在读完输入前就退出，忽略管道已关闭的错误
+            let _ = stdin.write_all(input.as_bytes());
         }
 
-        // 等待程序结束或超时
-        let output = std::thread::spawn(move || child.wait_with_output())
-            .join()
-            .map_err(|_| anyhow::anyhow!("Execution thread crashed"))??;
+        // stdout/stderr 用独立线程读，避免子进程写满管道时和下面的 wait4 互相卡死
+        let mut stdout_pipe = child.stdout.take().context("Failed to capture stdout")?;
+        let mut stderr_pipe = child.stderr.take().context("Failed to capture stderr")?;
+        let stdout_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stdout_pipe.read_to_end(&mut buf);
+            buf
+        });
+        let stderr_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr_pipe.read_to_end(&mut buf);
+            buf
+        });
+
+        // wait4 在独立线程里阻塞，主线程用 recv_timeout 实现墙钟超时
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut status: libc::c_int = 0;
+            let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+            let ret = unsafe { libc::wait4(pid, &mut status, 0, &mut rusage) };
+            let _ = tx.send((ret, status, rusage));
+        });
+
+        let (ret, status, rusage) = match rx.recv_timeout(time_limit) {
+            Ok(result) => result,
+            Err(_) => {
+                // 超时：杀掉子进程，然后阻塞等 wait4 线程把子进程收掉，
+                // 这样即便超时也能拿到一份 ru_maxrss
+                Self::kill_process(pid as u32);
+                let memory_used = rx.recv().ok().and_then(|(_, _, rusage)| Self::ru_maxrss_kb(&rusage));
+                let _ = stdout_reader.join();
+                let _ = stderr_reader.join();
+                return Ok(ExecutionResult {
+                    output: String::new(),
+                    exit_code: -1,
+                    execution_time: time_limit,
+                    memory_used,
+                    error: Some("Timeout".to_string()),
+                });
+            }
+        };
 
         let execution_time = start.elapsed();
+        let stdout_bytes = stdout_reader.join().unwrap_or_default();
+        let stderr_bytes = stderr_reader.join().unwrap_or_default();
 
-        // Check for timeout
-        if execution_time > time_limit {
+        if ret < 0 {
+            anyhow::bail!("wait4 失败");
+        }
+
+        let memory_used = Self::ru_maxrss_kb(&rusage);
+
+        // 超出内存限制
+        if let (Some(limit_mb), Some(used_kb)) = (memory_limit_mb, memory_used) {
+            if used_kb > limit_mb * 1024 {
+                return Ok(ExecutionResult {
+                    output: String::new(),
+                    exit_code: -1,
+                    execution_time,
+                    memory_used,
+                    error: Some("MemoryLimitExceeded".to_string()),
+                });
+            }
+        }
+
+        let stdout = String::from_utf8_lossy(&stdout_bytes).to_string();
+        let stderr = String::from_utf8_lossy(&stderr_bytes).to_string();
+
+        if libc::WIFSIGNALED(status) {
+            let sig = libc::WTERMSIG(status);
+            // RLIMIT_CPU 触发的是 SIGXCPU，直接归类为 TLE 而不是普通的 Runtime Error
+            let error = if sig == libc::SIGXCPU {
+                "Timeout".to_string()
+            } else {
+                format!("Process terminated by signal {}", sig)
+            };
             return Ok(ExecutionResult {
-                output: String::new(),
+                output: stdout,
                 exit_code: -1,
                 execution_time,
-                memory_used: None,
-                error: Some("Timeout".to_string()),
+                memory_used,
+                error: Some(error),
             });
         }
 
+        let exit_code = if libc::WIFEXITED(status) {
+            libc::WEXITSTATUS(status)
+        } else {
+            -1
+        };
+
+        let error = if exit_code != 0 {
+            Some(format!(
+                "Process exited abnormally, exit code: {}",
+                exit_code
+            ))
+        } else if !stderr.is_empty() {
+            Some(stderr)
+        } else {
+            None
+        };
+
+        Ok(ExecutionResult {
+            output: stdout,
+            exit_code,
+            execution_time,
+            memory_used,
+            error,
+        })
+    }
+
+    /// 从 rusage 中取出 `ru_maxrss`（Linux 上单位已经是 KB），0 视为未采集到
+    #[cfg(unix)]
+    fn ru_maxrss_kb(rusage: &libc::rusage) -> Option<u64> {
+        if rusage.ru_maxrss > 0 {
+            Some(rusage.ru_maxrss as u64)
+        } else {
+            None
+        }
+    }
+
+    /// 非 Unix 平台没有 `pre_exec`/`wait4`，退化为 spawn + 线程超时的旧实现，
+    /// 内存使用量无法采集
+    #[cfg(not(unix))]
+    fn spawn_and_wait_other(
+        executable: &Path,
+        input: &str,
+        language: Language,
+        time_limit: Duration,
+        memory_limit_mb: Option<u64>,
+    ) -> Result<ExecutionResult> {
+        let start = Instant::now();
+        let mut child = Self::build_command(executable, language)?
+            .spawn()
+            .with_context(|| format!("Cannot start program: {:?}", executable))?;
+
+        let pid = child.id();
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = child.wait_with_output();
+            let _ = tx.send(result);
+        });
+
+        let output = match rx.recv_timeout(time_limit) {
+            Ok(result) => result?,
+            Err(_) => {
+                Self::kill_process(pid);
+                return Ok(ExecutionResult {
+                    output: String::new(),
+                    exit_code: -1,
+                    execution_time: time_limit,
+                    memory_used: None,
+                    error: Some("Timeout".to_string()),
+                });
+            }
+        };
+
+        let execution_time = start.elapsed();
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let _ = memory_limit_mb; // 该平台无法采集内存用量，限制无法实施
 
-        let error = if !stderr.is_empty() {
+        let error = if !output.status.success() {
+            let reason = Self::abnormal_exit_reason(&output.status);
+            Some(reason.unwrap_or(stderr))
+        } else if !stderr.is_empty() {
             Some(stderr)
         } else {
             None
@@ -207,6 +428,28 @@ impl Executor {
         })
     }
 
+    /// 终止失控的子进程
+    #[cfg(unix)]
+    fn kill_process(pid: u32) {
+        let _ = Command::new("kill")
+            .args(["-9", &pid.to_string()])
+            .status();
+    }
+
+    #[cfg(windows)]
+    fn kill_process(pid: u32) {
+        let _ = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/F"])
+            .status();
+    }
+
+    /// 异常退出时的说明文字；始终返回 `None`，调用方因此退回使用 stderr（仅非 Unix 回退路径使用：
+    /// Unix 路径已经通过 wait4 拿到原始 status，直接用 WIFSIGNALED/WTERMSIG 判断）
+    #[cfg(not(unix))]
+    fn abnormal_exit_reason(_status: &std::process::ExitStatus) -> Option<String> {
+        None
+    }
+
     /// Find C++ compiler
     fn find_cpp_compiler(&self) -> Result<String> {
         // Try common C++ compilers
@@ -1,11 +1,17 @@
 use crate::executor::Executor;
-use crate::models::{ExecutionResult, TestCase, TestStatus};
+use crate::models::{
+    CheckerMode, DiffLine, DiffResult, ExecutionResult, Language, TestCase, TestStatus,
+};
 use anyhow::Result;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
 /// 测试判断器
+#[derive(Clone)]
 pub struct Judge {
     executor: Executor,
     compiled_executable: Option<PathBuf>,
@@ -19,18 +25,24 @@ impl Judge {
         })
     }
 
-    /// 编译一次，返回可执行文件路径
+    /// 编译一次，把可执行文件路径记在 `compiled_executable` 里供 `run_test` 使用。`stop_signal` 置位时直接放弃编译
+    /// （编译本身是同步调用，无法中途打断，只能在开始前做这一次协作式检查）
     pub fn compile_once(
         &mut self,
         source_file: &Path,
+        language: Language,
         stop_signal: Option<Arc<AtomicBool>>,
     ) -> Result<()> {
+        if Self::is_cancelled(&stop_signal) {
+            anyhow::bail!("Compilation cancelled");
+        }
+
         // 如果已经编译过，先清理
         if self.compiled_executable.is_some() {
             self.cleanup();
         }
 
-        match self.executor.compile(source_file, stop_signal) {
+        match self.executor.compile(source_file, language) {
             Ok(exe) => {
                 self.compiled_executable = Some(exe);
                 Ok(())
@@ -43,8 +55,18 @@ impl Judge {
     pub async fn run_test(
         &self,
         test: &mut TestCase,
+        language: Language,
+        time_limit: Duration,
+        memory_limit_mb: Option<u64>,
+        checker: &CheckerMode,
         stop_signal: Option<Arc<AtomicBool>>,
     ) -> Result<()> {
+        if Self::is_cancelled(&stop_signal) {
+            test.status = TestStatus::RuntimeError;
+            test.error_message = Some("Cancelled".to_string());
+            return Ok(());
+        }
+
         test.status = TestStatus::Running;
 
         let executable = self
@@ -53,35 +75,49 @@ impl Judge {
             .ok_or_else(|| anyhow::anyhow!("No compiled executable found"))?;
 
         // 执行代码
-        let result = self
-            .executor
-            .execute(executable, &test.input, stop_signal)?;
+        let result = self.executor.execute(
+            executable,
+            &test.input,
+            language,
+            time_limit,
+            memory_limit_mb,
+        )?;
 
         // 更新测试结果
-        self.update_test_from_result(test, result);
+        self.update_test_from_result(test, result, checker);
 
         Ok(())
     }
 
     /// 清理编译产物
     pub fn cleanup(&mut self) {
-        if self.compiled_executable.is_some() {
-            self.executor.cleanup();
-            self.compiled_executable = None;
+        if let Some(exe) = self.compiled_executable.take() {
+            self.executor.cleanup(&[exe]);
         }
     }
 
     /// 判断单个测试用例（编译并运行）
+    #[allow(clippy::too_many_arguments)]
     pub async fn judge_test(
         &self,
         source_file: &Path,
+        language: Language,
         test: &mut TestCase,
+        time_limit: Duration,
+        memory_limit_mb: Option<u64>,
+        checker: &CheckerMode,
         stop_signal: Option<Arc<AtomicBool>>,
     ) -> Result<()> {
+        if Self::is_cancelled(&stop_signal) {
+            test.status = TestStatus::RuntimeError;
+            test.error_message = Some("Cancelled".to_string());
+            return Ok(());
+        }
+
         test.status = TestStatus::Running;
 
         // 编译代码
-        let executable = match self.executor.compile(source_file, stop_signal.clone()) {
+        let executable = match self.executor.compile(source_file, language) {
             Ok(exe) => exe,
             Err(e) => {
                 test.status = TestStatus::CompilationError;
@@ -91,28 +127,129 @@ impl Judge {
         };
 
         // 执行代码
-        let result = self
-            .executor
-            .execute(&executable, &test.input, stop_signal)?;
+        let result = self.executor.execute(
+            &executable,
+            &test.input,
+            language,
+            time_limit,
+            memory_limit_mb,
+        )?;
 
         // 更新测试结果
-        self.update_test_from_result(test, result);
+        self.update_test_from_result(test, result, checker);
 
         // 清理编译产物 a.exe
-        self.executor.cleanup();
+        self.executor.cleanup(&[executable]);
+
+        Ok(())
+    }
+
+    /// 判断一组测试用例：只编译一次，再把各测试点派发到一个用 `Semaphore` 限流的
+    /// tokio 任务池里并发跑（`parallelism` 为 0 时退化为按 CPU 核数限流）。
+    /// 和逐个调用 `judge_test` 不同，所有任务共享同一份编译产物，不会出现
+    /// 多个任务并发编译/删除同一个 `source_file.with_extension("exe")` 的竞态。
+    /// 执行顺序本身是乱序的，但收集结果时按原始下标写回 `tests`，保证返回顺序
+    /// 和调用方传入的顺序一致。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn judge_all_tests(
+        &mut self,
+        source_file: &Path,
+        language: Language,
+        tests: &mut [TestCase],
+        time_limit: Duration,
+        memory_limit_mb: Option<u64>,
+        checker: &CheckerMode,
+        stop_signal: Option<Arc<AtomicBool>>,
+        parallelism: usize,
+    ) -> Result<()> {
+        if let Err(e) = self.compile_once(source_file, language, stop_signal.clone()) {
+            // 编译失败时把失败原因写回每个测试点，和 `judge_test` 单测编译失败的行为保持一致
+            for test in tests.iter_mut() {
+                test.status = TestStatus::CompilationError;
+                test.error_message = Some(format!("Compilation failed: {}", e));
+            }
+            return Ok(());
+        }
+
+        let judge = Arc::new(self.clone());
+        let permits = if parallelism == 0 {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        } else {
+            parallelism
+        };
+        let semaphore = Arc::new(Semaphore::new(permits));
+
+        let handles: Vec<_> = tests
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(idx, mut test)| {
+                let judge = judge.clone();
+                let semaphore = semaphore.clone();
+                let checker = checker.clone();
+                let stop_signal = stop_signal.clone();
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+
+                    test.reset();
+                    // 测试点自带的 checker 覆盖优先于运行时选择的全局 checker
+                    let effective_checker = test.checker.clone().unwrap_or_else(|| checker.clone());
+                    if let Err(e) = judge
+                        .run_test(
+                            &mut test,
+                            language,
+                            time_limit,
+                            memory_limit_mb,
+                            &effective_checker,
+                            stop_signal,
+                        )
+                        .await
+                    {
+                        test.status = TestStatus::RuntimeError;
+                        test.error_message = Some(format!("执行错误: {}", e));
+                    }
+
+                    (idx, test)
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (idx, result) = handle.await?;
+            tests[idx] = result;
+        }
+
+        self.cleanup();
 
         Ok(())
     }
 
+    fn is_cancelled(stop_signal: &Option<Arc<AtomicBool>>) -> bool {
+        stop_signal
+            .as_ref()
+            .map(|s| s.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
     /// 根据执行结果更新测试用例状态
-    fn update_test_from_result(&self, test: &mut TestCase, result: ExecutionResult) {
+    fn update_test_from_result(
+        &self,
+        test: &mut TestCase,
+        result: ExecutionResult,
+        checker: &CheckerMode,
+    ) {
         test.execution_time = Some(result.execution_time);
         test.memory_used = result.memory_used;
         test.actual_output = Some(result.output.clone());
+        test.diff = None;
 
         if let Some(error) = result.error {
             if error.contains("Timeout") {
                 test.status = TestStatus::TimeLimitExceeded;
+            } else if error.contains("MemoryLimitExceeded") {
+                test.status = TestStatus::MemoryLimitExceeded;
             } else {
                 test.status = TestStatus::RuntimeError;
                 test.error_message = Some(error);
@@ -129,11 +266,55 @@ impl Judge {
             return;
         }
 
-        // 比较输出
-        if self.compare_output(&test.expected_output, &result.output) {
-            test.status = TestStatus::Accepted;
+        // 按所选 checker 比较输出
+        match self.compare_with_checker(checker, &test.input, &test.expected_output, &result.output)
+        {
+            Ok((status, message)) => {
+                test.status = status;
+                if status != TestStatus::Accepted {
+                    test.diff = Some(Self::lcs_diff(
+                        &self.normalize_output(&test.expected_output),
+                        &self.normalize_output(&result.output),
+                    ));
+                }
+                if let Some(message) = message {
+                    test.error_message = Some(message);
+                }
+            }
+            Err(e) => {
+                test.status = TestStatus::RuntimeError;
+                test.error_message = Some(format!("Checker failed: {}", e));
+            }
+        }
+    }
+
+    /// 按选定的 checker 模式比较输出，返回 (判定结果, checker 给出的说明)
+    fn compare_with_checker(
+        &self,
+        checker: &CheckerMode,
+        input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<(TestStatus, Option<String>)> {
+        match checker {
+            CheckerMode::Exact => Ok((Self::verdict(self.compare_output(expected, actual)), None)),
+            CheckerMode::Token => Ok((Self::verdict(Self::compare_tokens(expected, actual)), None)),
+            CheckerMode::Float { abs_eps, rel_eps } => Ok((
+                Self::verdict(Self::compare_float_tokens(expected, actual, *abs_eps, *rel_eps)),
+                None,
+            )),
+            CheckerMode::External { program } => {
+                self.run_external_checker(program, input, expected, actual)
+            }
+        }
+    }
+
+    /// 把一个简单的「是否通过」折叠成 `Accepted`/`WrongAnswer`
+    fn verdict(accepted: bool) -> TestStatus {
+        if accepted {
+            TestStatus::Accepted
         } else {
-            test.status = TestStatus::WrongAnswer;
+            TestStatus::WrongAnswer
         }
     }
 
@@ -146,6 +327,145 @@ impl Judge {
         expected_normalized == actual_normalized
     }
 
+    /// 按空白分词比较，忽略行尾/行数差异
+    fn compare_tokens(expected: &str, actual: &str) -> bool {
+        expected.split_whitespace().eq(actual.split_whitespace())
+    }
+
+    /// 按 token 数值比较，数值在给定绝对/相对误差内视为相等，非数值 token 退化为字符串比较
+    fn compare_float_tokens(expected: &str, actual: &str, abs_eps: f64, rel_eps: f64) -> bool {
+        let expected_tokens: Vec<&str> = expected.split_whitespace().collect();
+        let actual_tokens: Vec<&str> = actual.split_whitespace().collect();
+
+        if expected_tokens.len() != actual_tokens.len() {
+            return false;
+        }
+
+        expected_tokens
+            .iter()
+            .zip(actual_tokens.iter())
+            .all(|(e, a)| match (e.parse::<f64>(), a.parse::<f64>()) {
+                (Ok(ev), Ok(av)) => {
+                    let diff = (ev - av).abs();
+                    diff <= abs_eps || diff <= rel_eps * ev.abs().max(av.abs())
+                }
+                _ => e == a,
+            })
+    }
+
+    /// 调用外部 testlib 风格 checker：`checker <input> <output> <answer>`。
+    /// 优先解析 stdout 首行的 `ok`/`wrong answer`/`presentation error` 标签判定verdict
+    /// （`pe` 单独映射为 `PresentationError`，不和 `WrongAnswer` 混为一谈），
+    /// 标签无法识别时（checker 不遵循这个约定）退化为按退出码判断
+    fn run_external_checker(
+        &self,
+        program: &str,
+        input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<(TestStatus, Option<String>)> {
+        let temp_dir = std::env::temp_dir();
+        let run_id = uuid::Uuid::new_v4();
+        let input_path = temp_dir.join(format!("cpkit-{}-input", run_id));
+        let output_path = temp_dir.join(format!("cpkit-{}-output", run_id));
+        let answer_path = temp_dir.join(format!("cpkit-{}-answer", run_id));
+
+        std::fs::write(&input_path, input)?;
+        std::fs::write(&output_path, actual)?;
+        std::fs::write(&answer_path, expected)?;
+
+        let output = Command::new(program)
+            .arg(&input_path)
+            .arg(&output_path)
+            .arg(&answer_path)
+            .output();
+
+        let _ = std::fs::remove_file(&input_path);
+        let _ = std::fs::remove_file(&output_path);
+        let _ = std::fs::remove_file(&answer_path);
+
+        let output = output?;
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let verdict_tag = stdout.to_lowercase();
+
+        let status = if verdict_tag.starts_with("ok") {
+            TestStatus::Accepted
+        } else if verdict_tag.starts_with("wrong answer") || verdict_tag.starts_with("wa") {
+            TestStatus::WrongAnswer
+        } else if verdict_tag.starts_with("presentation error") || verdict_tag.starts_with("pe") {
+            TestStatus::PresentationError
+        } else if output.status.success() {
+            TestStatus::Accepted
+        } else {
+            TestStatus::WrongAnswer
+        };
+
+        let message = if !stdout.is_empty() {
+            Some(stdout)
+        } else if !stderr.is_empty() {
+            Some(stderr)
+        } else {
+            None
+        };
+
+        Ok((status, message))
+    }
+
+    /// 对规范化后的期望/实际输出按行做最长公共子序列 diff：
+    /// 标准 dp[i][j] 表（后缀形式，`dp[i][j]` = `a[i..]`/`b[j..]` 的 LCS 长度），
+    /// 再从头回溯一遍产出 Equal/Deleted/Inserted 序列
+    fn lcs_diff(expected: &str, actual: &str) -> DiffResult {
+        let a: Vec<&str> = expected.lines().collect();
+        let b: Vec<&str> = actual.lines().collect();
+        let (m, n) = (a.len(), b.len());
+
+        let mut dp = vec![vec![0usize; n + 1]; m + 1];
+        for i in (0..m).rev() {
+            for j in (0..n).rev() {
+                dp[i][j] = if a[i] == b[j] {
+                    dp[i + 1][j + 1] + 1
+                } else {
+                    dp[i + 1][j].max(dp[i][j + 1])
+                };
+            }
+        }
+
+        let mut lines = Vec::with_capacity(m + n);
+        let (mut i, mut j) = (0, 0);
+        while i < m && j < n {
+            if a[i] == b[j] {
+                lines.push(DiffLine::Equal(a[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if dp[i + 1][j] >= dp[i][j + 1] {
+                lines.push(DiffLine::Deleted(a[i].to_string()));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Inserted(b[j].to_string()));
+                j += 1;
+            }
+        }
+        while i < m {
+            lines.push(DiffLine::Deleted(a[i].to_string()));
+            i += 1;
+        }
+        while j < n {
+            lines.push(DiffLine::Inserted(b[j].to_string()));
+            j += 1;
+        }
+
+        let first_mismatch_line = lines
+            .iter()
+            .position(|line| !matches!(line, DiffLine::Equal(_)))
+            .map(|idx| idx + 1);
+
+        DiffResult {
+            lines,
+            first_mismatch_line,
+        }
+    }
+
     /// 规范化输出
     fn normalize_output(&self, output: &str) -> String {
         output
@@ -156,62 +476,138 @@ impl Judge {
             .trim()
             .to_string()
     }
+}
 
-    /// 判断所有测试用例
-    #[allow(dead_code)]
-    pub async fn judge_all_tests(
-        &self,
-        source_file: &Path,
-        tests: &mut [TestCase],
-        stop_signal: Option<Arc<AtomicBool>>,
-    ) -> Result<JudgeStatistics> {
-        let mut stats = JudgeStatistics::default();
-        stats.total = tests.len();
-
-        for test in tests.iter_mut() {
-            self.judge_test(source_file, test, stop_signal.clone())
-                .await?;
-
-            match test.status {
-                TestStatus::Accepted => stats.passed += 1,
-                TestStatus::WrongAnswer => stats.wrong_answer += 1,
-                TestStatus::RuntimeError => stats.runtime_error += 1,
-                TestStatus::TimeLimitExceeded => stats.time_limit_exceeded += 1,
-                TestStatus::MemoryLimitExceeded => stats.memory_limit_exceeded += 1,
-                TestStatus::CompilationError => stats.compilation_error += 1,
-                _ => {}
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DiffLine;
 
-        Ok(stats)
+    #[test]
+    fn lcs_diff_identical_input_is_all_equal() {
+        let diff = Judge::lcs_diff("1\n2\n3", "1\n2\n3");
+        assert_eq!(
+            diff.lines,
+            vec![
+                DiffLine::Equal("1".to_string()),
+                DiffLine::Equal("2".to_string()),
+                DiffLine::Equal("3".to_string()),
+            ]
+        );
+        assert_eq!(diff.first_mismatch_line, None);
     }
-}
 
-/// 判断统计信息
-#[derive(Debug, Default, Clone)]
-#[allow(dead_code)]
-pub struct JudgeStatistics {
-    pub total: usize,
-    pub passed: usize,
-    pub wrong_answer: usize,
-    pub runtime_error: usize,
-    pub time_limit_exceeded: usize,
-    pub memory_limit_exceeded: usize,
-    pub compilation_error: usize,
-}
+    #[test]
+    fn lcs_diff_reports_first_mismatch_line() {
+        let diff = Judge::lcs_diff("a\nb\nc", "a\nx\nc");
+        assert_eq!(diff.first_mismatch_line, Some(2));
+        assert_eq!(
+            diff.lines,
+            vec![
+                DiffLine::Equal("a".to_string()),
+                DiffLine::Deleted("b".to_string()),
+                DiffLine::Inserted("x".to_string()),
+                DiffLine::Equal("c".to_string()),
+            ]
+        );
+    }
 
-impl JudgeStatistics {
-    #[allow(dead_code)]
-    pub fn all_passed(&self) -> bool {
-        self.passed == self.total && self.total > 0
+    #[test]
+    fn lcs_diff_handles_pure_insertion_and_deletion() {
+        let diff = Judge::lcs_diff("a\nb", "a\nb\nc");
+        assert_eq!(
+            diff.lines,
+            vec![
+                DiffLine::Equal("a".to_string()),
+                DiffLine::Equal("b".to_string()),
+                DiffLine::Inserted("c".to_string()),
+            ]
+        );
     }
 
-    #[allow(dead_code)]
-    pub fn success_rate(&self) -> f32 {
-        if self.total == 0 {
-            0.0
-        } else {
-            (self.passed as f32 / self.total as f32) * 100.0
+    #[test]
+    fn compare_float_tokens_within_absolute_epsilon() {
+        assert!(Judge::compare_float_tokens("1.0 2.0", "1.0001 2.0001", 1e-3, 0.0));
+        assert!(!Judge::compare_float_tokens("1.0 2.0", "1.1 2.0", 1e-3, 0.0));
+    }
+
+    #[test]
+    fn compare_float_tokens_within_relative_epsilon() {
+        // 1e6 * 1e-3 relative tolerance = 1000，差值 500 应当通过
+        assert!(Judge::compare_float_tokens("1000000", "1000500", 0.0, 1e-3));
+    }
+
+    #[test]
+    fn compare_float_tokens_mismatched_token_count_fails() {
+        assert!(!Judge::compare_float_tokens("1.0 2.0", "1.0", 1e-3, 1e-3));
+    }
+
+    #[test]
+    fn compare_float_tokens_falls_back_to_string_equality_when_not_numeric() {
+        // 无法解析为 f64 的 token 退化为字符串比较：相同字符串通过，不同字符串不通过
+        assert!(Judge::compare_float_tokens("ok yes", "ok yes", 1e-3, 1e-3));
+        assert!(!Judge::compare_float_tokens("ok yes", "ok no", 1e-3, 1e-3));
+        // 一侧能解析、一侧不能解析时也走字符串比较，而不是 panic 或误判通过
+        assert!(!Judge::compare_float_tokens("1.0", "abc", 1e-3, 1e-3));
+    }
+
+    #[test]
+    fn run_external_checker_parses_ok_verdict() {
+        let judge = Judge::new().unwrap();
+        let script = write_checker_script("echo 'ok matched'; exit 1");
+        let (status, message) = judge
+            .run_external_checker(script.to_str().unwrap(), "in", "exp", "act")
+            .unwrap();
+        assert_eq!(status, TestStatus::Accepted);
+        assert_eq!(message.as_deref(), Some("ok matched"));
+        let _ = std::fs::remove_file(script);
+    }
+
+    #[test]
+    fn run_external_checker_parses_wrong_answer_verdict() {
+        let judge = Judge::new().unwrap();
+        let script = write_checker_script("echo 'wrong answer: mismatch'; exit 0");
+        let (status, _) = judge
+            .run_external_checker(script.to_str().unwrap(), "in", "exp", "act")
+            .unwrap();
+        assert_eq!(status, TestStatus::WrongAnswer);
+        let _ = std::fs::remove_file(script);
+    }
+
+    #[test]
+    fn run_external_checker_parses_presentation_error_verdict() {
+        let judge = Judge::new().unwrap();
+        let script = write_checker_script("echo 'pe: extra whitespace'; exit 0");
+        let (status, _) = judge
+            .run_external_checker(script.to_str().unwrap(), "in", "exp", "act")
+            .unwrap();
+        // `pe` 必须映射为 PresentationError，不能和 WrongAnswer 混为一谈
+        assert_eq!(status, TestStatus::PresentationError);
+        let _ = std::fs::remove_file(script);
+    }
+
+    #[test]
+    fn run_external_checker_falls_back_to_exit_code_without_known_tag() {
+        let judge = Judge::new().unwrap();
+        let script = write_checker_script("echo 'something unrelated'; exit 0");
+        let (status, _) = judge
+            .run_external_checker(script.to_str().unwrap(), "in", "exp", "act")
+            .unwrap();
+        assert_eq!(status, TestStatus::Accepted);
+        let _ = std::fs::remove_file(script);
+    }
+
+    /// 写一个可执行的 shell 脚本作为测试用的假 checker，返回脚本路径
+    fn write_checker_script(body: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cpkit-test-checker-{}.sh", uuid::Uuid::new_v4()));
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&path).unwrap().permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&path, perms).unwrap();
         }
+        path
     }
 }
@@ -0,0 +1,16 @@
+pub mod app;
+pub mod backend;
+pub mod competitive_companion;
+pub mod executor;
+pub mod judge;
+pub mod models;
+pub mod sandbox;
+pub mod search_index;
+pub mod storage;
+pub mod ui;
+
+// 旧的 gpui/gpui_component 技术预览界面，见 Cargo.toml 里 `gpui-stub` feature 的说明。
+#[cfg(feature = "gpui-stub")]
+pub mod test_case_card;
+#[cfg(feature = "gpui-stub")]
+pub mod test_case_panel;
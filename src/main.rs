@@ -1,11 +1,7 @@
+use cpkit::test_case_panel::TestCasePanel;
 use gpui::*;
 use gpui_component::*;
 
-mod test_case_card;
-mod test_case_panel;
-
-use crate::test_case_panel::TestCasePanel;
-
 fn main() {
     let app = Application::new();
 
@@ -18,6 +18,14 @@ pub struct Problem {
     pub language: Language,
     pub created_at: DateTime<Utc>,
     pub last_run: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub checker: CheckerMode,
+    /// 压力测试用的随机数据生成器（可执行程序路径；运行时以迭代序号作为唯一的命令行参数）
+    #[serde(default)]
+    pub stress_generator: String,
+    /// 压力测试用的暴力解法源文件路径，作为对拍的基准答案
+    #[serde(default)]
+    pub stress_brute: String,
 }
 
 impl Problem {
@@ -35,6 +43,9 @@ impl Problem {
             language: Language::Cpp,
             created_at: Utc::now(),
             last_run: None,
+            checker: CheckerMode::default(),
+            stress_generator: String::new(),
+            stress_brute: String::new(),
         }
     }
 
@@ -54,6 +65,13 @@ pub struct TestCase {
     pub execution_time: Option<Duration>,
     pub memory_used: Option<u64>,
     pub error_message: Option<String>,
+    /// 本测试点的 checker 覆盖；为 `None` 时沿用运行时选择的全局 checker
+    /// （用于存在多组合法解、需要对单个测试点单独指定 special judge 的场景）
+    #[serde(default)]
+    pub checker: Option<CheckerMode>,
+    /// WrongAnswer 时期望/实际输出之间的统一 diff，由 `Judge` 在判题时计算并存入
+    #[serde(default)]
+    pub diff: Option<DiffResult>,
 }
 
 impl TestCase {
@@ -67,6 +85,8 @@ impl TestCase {
             execution_time: None,
             memory_used: None,
             error_message: None,
+            checker: None,
+            diff: None,
         }
     }
 
@@ -76,6 +96,44 @@ impl TestCase {
         self.execution_time = None;
         self.memory_used = None;
         self.error_message = None;
+        self.diff = None;
+    }
+}
+
+/// 输出规范化流水线：去除行尾空白、折叠结尾空行，
+/// 并依次应用用户配置的正则替换（捕获组 -> 替换文本）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NormalizationConfig {
+    /// 按顺序应用的 (正则, 替换文本) 列表，用于屏蔽易变的 token（时间戳、地址等）
+    pub regex_substitutions: Vec<(String, String)>,
+}
+
+impl NormalizationConfig {
+    pub fn normalize(&self, text: &str) -> String {
+        // 去除每行的行尾空白，统一行尾
+        let mut normalized: String = text
+            .lines()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // 折叠结尾处的空行
+        while normalized.ends_with('\n') {
+            normalized.pop();
+        }
+
+        for (pattern, replacement) in &self.regex_substitutions {
+            match regex::Regex::new(pattern) {
+                Ok(re) => {
+                    normalized = re.replace_all(&normalized, replacement.as_str()).to_string();
+                }
+                Err(e) => {
+                    tracing::warn!("无效的规范化正则 '{}': {}", pattern, e);
+                }
+            }
+        }
+
+        normalized
     }
 }
 
@@ -90,6 +148,8 @@ pub enum TestStatus {
     TimeLimitExceeded,
     MemoryLimitExceeded,
     CompilationError,
+    /// 内容正确，但输出格式（多余/缺失的行尾空白或换行符）不符——区别于内容就不对的 WrongAnswer
+    PresentationError,
 }
 
 impl TestStatus {
@@ -103,6 +163,7 @@ impl TestStatus {
             TestStatus::TimeLimitExceeded => "⏱",
             TestStatus::MemoryLimitExceeded => "💾",
             TestStatus::CompilationError => "🔨",
+            TestStatus::PresentationError => "📝",
         }
     }
 
@@ -116,6 +177,7 @@ impl TestStatus {
             TestStatus::TimeLimitExceeded => egui::Color32::YELLOW,
             TestStatus::MemoryLimitExceeded => egui::Color32::GOLD,
             TestStatus::CompilationError => egui::Color32::DARK_RED,
+            TestStatus::PresentationError => egui::Color32::from_rgb(180, 100, 220),
         }
     }
 
@@ -129,10 +191,75 @@ impl TestStatus {
             TestStatus::TimeLimitExceeded => "Time Limit Exceeded",
             TestStatus::MemoryLimitExceeded => "Memory Limit Exceeded",
             TestStatus::CompilationError => "Compilation Error",
+            TestStatus::PresentationError => "Presentation Error",
         }
     }
 }
 
+/// 输出比较模式（支持多解问题的 special judge）
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum CheckerMode {
+    /// 逐字符精确比较（规范化后）
+    #[default]
+    Exact,
+    /// 按空白分词后逐 token 比较
+    Token,
+    /// 按 token 数值比较，容忍绝对/相对误差
+    Float { abs_eps: f64, rel_eps: f64 },
+    /// 外部 testlib 风格 checker：`checker <input> <output> <answer>`
+    External { program: String },
+}
+
+impl CheckerMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckerMode::Exact => "Exact",
+            CheckerMode::Token => "Token",
+            CheckerMode::Float { .. } => "Float",
+            CheckerMode::External { .. } => "External",
+        }
+    }
+
+    pub fn all_labels() -> &'static [&'static str] {
+        &["Exact", "Token", "Float", "External"]
+    }
+}
+
+/// 统一 diff 中的一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DiffLine {
+    /// 期望输出与实际输出在这一行一致
+    Equal(String),
+    /// 仅存在于期望输出（对应 unified diff 里的 `-`）
+    Deleted(String),
+    /// 仅存在于实际输出（对应 unified diff 里的 `+`）
+    Inserted(String),
+}
+
+/// `WrongAnswer` 时期望输出与实际输出之间的统一行 diff
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffResult {
+    pub lines: Vec<DiffLine>,
+    /// 第一处不一致在 `lines` 中的位置（1-based），供 GUI 滚动定位
+    pub first_mismatch_line: Option<usize>,
+}
+
+impl DiffResult {
+    /// 渲染成带 `-`/`+`/空格前缀的 unified hunk 文本
+    #[allow(dead_code)]
+    pub fn render_unified(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| match line {
+                DiffLine::Equal(s) => format!("  {}", s),
+                DiffLine::Deleted(s) => format!("- {}", s),
+                DiffLine::Inserted(s) => format!("+ {}", s),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 /// 编程语言
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Language {
@@ -233,3 +360,82 @@ impl ExecutionResult {
         self.exit_code == 0 && self.error.is_none()
     }
 }
+
+/// 一次批量运行的汇总统计（类似测试跑批工具的 stats block）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub total: usize,
+    pub accepted: usize,
+    pub wrong_answer: usize,
+    pub runtime_error: usize,
+    pub time_limit_exceeded: usize,
+    pub memory_limit_exceeded: usize,
+    pub compilation_error: usize,
+    pub total_wall_time: Duration,
+    pub max_execution_time: Option<Duration>,
+    pub mean_execution_time: Option<Duration>,
+    pub peak_memory_kb: Option<u64>,
+    pub slowest_test_id: Option<Uuid>,
+}
+
+impl RunStats {
+    /// 根据一批测试点的最终状态计算汇总统计
+    pub fn from_tests(tests: &[TestCase], total_wall_time: Duration) -> Self {
+        let mut stats = RunStats {
+            total: tests.len(),
+            total_wall_time,
+            ..Default::default()
+        };
+
+        let mut time_sum = Duration::default();
+        let mut time_count: u32 = 0;
+
+        for test in tests {
+            match test.status {
+                TestStatus::Accepted => stats.accepted += 1,
+                TestStatus::WrongAnswer => stats.wrong_answer += 1,
+                TestStatus::RuntimeError => stats.runtime_error += 1,
+                TestStatus::TimeLimitExceeded => stats.time_limit_exceeded += 1,
+                TestStatus::MemoryLimitExceeded => stats.memory_limit_exceeded += 1,
+                TestStatus::CompilationError => stats.compilation_error += 1,
+                _ => {}
+            }
+
+            if let Some(exec_time) = test.execution_time {
+                time_sum += exec_time;
+                time_count += 1;
+                if stats.max_execution_time.is_none_or(|max| exec_time > max) {
+                    stats.max_execution_time = Some(exec_time);
+                    stats.slowest_test_id = Some(test.id);
+                }
+            }
+
+            if let Some(mem) = test.memory_used {
+                if stats.peak_memory_kb.is_none_or(|peak| mem > peak) {
+                    stats.peak_memory_kb = Some(mem);
+                }
+            }
+        }
+
+        if time_count > 0 {
+            stats.mean_execution_time = Some(time_sum / time_count);
+        }
+
+        stats
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.accepted == self.total && self.total > 0
+    }
+}
+
+/// 一次判题运行的历史记录条目，追加持久化在源文件对应的 `.cpkit` 历史文件里，
+/// 供 GUI 画出 "3/10 -> 10/10" 这样的趋势、定位两次编辑之间的回归
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunHistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    /// 本次运行所用语言的显示名（取自 `Language::display_name`，例如 "C++"、"Python"）；
+    /// 不记录具体的编译器/解释器程序名
+    pub compiler: String,
+    pub stats: RunStats,
+}
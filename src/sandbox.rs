@@ -0,0 +1,335 @@
+use crate::models::ExecutionResult;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Duration;
+
+/// 在隔离的子进程中运行一个已编译好的可执行文件，强制墙钟、CPU 时间
+/// 与内存地址空间限制，返回与 `Executor::execute` 相同的 `ExecutionResult`
+/// （`error` 字段复用 "Timeout"/"MemoryLimitExceeded" 约定，供 `Judge` 分类
+/// TLE/MLE），因此调用方无需区分结果来自沙箱还是普通 spawn。
+///
+/// Linux 下使用 fork + setrlimit(+ cgroup v2) 实现真正的资源隔离；
+/// 其他平台没有等价机制，退化为不带隔离的直接 spawn，仅保证 GUI 仍可运行。
+#[cfg(target_os = "linux")]
+pub fn run_guarded(
+    executable: &Path,
+    input: &str,
+    wall_time_limit: Duration,
+    cpu_time_limit: Duration,
+    memory_limit_mb: Option<u64>,
+) -> Result<ExecutionResult> {
+    linux::run_guarded(
+        executable,
+        input,
+        wall_time_limit,
+        cpu_time_limit,
+        memory_limit_mb,
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn run_guarded(
+    executable: &Path,
+    input: &str,
+    wall_time_limit: Duration,
+    _cpu_time_limit: Duration,
+    memory_limit_mb: Option<u64>,
+) -> Result<ExecutionResult> {
+    passthrough::run_guarded(executable, input, wall_time_limit, memory_limit_mb)
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::ffi::CString;
+    use std::fs;
+    use std::time::Instant;
+
+    pub fn run_guarded(
+        executable: &Path,
+        input: &str,
+        wall_time_limit: Duration,
+        cpu_time_limit: Duration,
+        memory_limit_mb: Option<u64>,
+    ) -> Result<ExecutionResult> {
+        let run_id = uuid::Uuid::new_v4();
+        let temp_dir = std::env::temp_dir();
+        let input_path = temp_dir.join(format!("cpkit-sandbox-{}-in", run_id));
+        let output_path = temp_dir.join(format!("cpkit-sandbox-{}-out", run_id));
+        fs::write(&input_path, input).context("写入沙箱输入文件失败")?;
+        fs::write(&output_path, "").context("创建沙箱输出文件失败")?;
+
+        let cgroup_dir = try_create_cgroup(&run_id);
+
+        let exe_c = CString::new(
+            executable
+                .to_str()
+                .context("可执行文件路径包含非 UTF-8 字符")?,
+        )?;
+        let input_c = CString::new(input_path.to_str().unwrap())?;
+        let output_c = CString::new(output_path.to_str().unwrap())?;
+
+        let cpu_secs = cpu_time_limit.as_secs().max(1);
+        let mem_bytes = memory_limit_mb.map(|mb| mb * 1024 * 1024);
+
+        let start = Instant::now();
+
+        // SAFETY: 子进程分支只调用 async-signal-safe 的系统调用
+        // （open/dup2/close/setrlimit/execvp/_exit），不触碰 Rust 运行时状态。
+        let pid = unsafe { libc::fork() };
+        if pid < 0 {
+            let _ = fs::remove_file(&input_path);
+            let _ = fs::remove_file(&output_path);
+            anyhow::bail!("fork 失败");
+        }
+
+        if pid == 0 {
+            unsafe {
+                // 独立进程组：父进程超时后可以一次性 kill(-pid) 杀掉整组，避免留下孤儿进程
+                libc::setpgid(0, 0);
+
+                // 用 dup2 把测试的输入/输出文件接到 stdin/stdout 上
+                // （这里没有现成的 FILE* 句柄，所以用 dup2 代替 sketch 里的 freopen）
+                let in_fd = libc::open(input_c.as_ptr(), libc::O_RDONLY);
+                if in_fd >= 0 {
+                    libc::dup2(in_fd, libc::STDIN_FILENO);
+                    libc::close(in_fd);
+                }
+                let out_fd = libc::open(output_c.as_ptr(), libc::O_WRONLY | libc::O_TRUNC, 0o644);
+                if out_fd >= 0 {
+                    libc::dup2(out_fd, libc::STDOUT_FILENO);
+                    libc::close(out_fd);
+                }
+
+                // CPU 时间限制：超过后内核向进程发送 SIGXCPU
+                let cpu_limit = libc::rlimit {
+                    rlim_cur: cpu_secs,
+                    rlim_max: cpu_secs,
+                };
+                let _ = libc::setrlimit(libc::RLIMIT_CPU, &cpu_limit);
+
+                // 地址空间限制：超过后 malloc/brk 失败，程序通常以非零退出码或 SIGSEGV 终止
+                if let Some(bytes) = mem_bytes {
+                    let as_limit = libc::rlimit {
+                        rlim_cur: bytes,
+                        rlim_max: bytes,
+                    };
+                    let _ = libc::setrlimit(libc::RLIMIT_AS, &as_limit);
+                }
+
+                let argv = [exe_c.as_ptr(), std::ptr::null()];
+                libc::execvp(exe_c.as_ptr(), argv.as_ptr());
+                // execvp 只有失败时才会返回到这里
+                libc::_exit(127);
+            }
+        }
+
+        // 父进程：如果 cgroup 可用，把子进程放进去，这样退出后才能读到 memory.peak
+        if let Some(dir) = &cgroup_dir {
+            let _ = fs::write(dir.join("cgroup.procs"), pid.to_string());
+        }
+
+        // 墙钟计时器独立于 RLIMIT_CPU 轮询：挂起等待 I/O 的子进程不消耗 CPU 时间，
+        // 不会触发 SIGXCPU，必须由父进程自己兜底杀掉
+        let wall_deadline = Instant::now() + wall_time_limit;
+        let mut status: libc::c_int = 0;
+        let mut timed_out = false;
+        loop {
+            let ret = unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) };
+            if ret == pid {
+                break;
+            }
+            if Instant::now() >= wall_deadline {
+                timed_out = true;
+                unsafe {
+                    libc::kill(-pid, libc::SIGKILL);
+                    libc::waitpid(pid, &mut status, 0);
+                }
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let execution_time = start.elapsed();
+
+        // 峰值内存：优先 cgroup v2 的 memory.peak；不可用（未挂载/无权限）时
+        // 回退到 getrusage(RUSAGE_CHILDREN) 的 ru_maxrss
+        let memory_used_kb = cgroup_dir
+            .as_ref()
+            .and_then(|dir| read_cgroup_memory_peak_kb(dir))
+            .or_else(read_rusage_children_maxrss_kb);
+
+        if let Some(dir) = &cgroup_dir {
+            let _ = fs::remove_dir(dir);
+        }
+
+        let output = fs::read_to_string(&output_path).unwrap_or_default();
+        let _ = fs::remove_file(&input_path);
+        let _ = fs::remove_file(&output_path);
+
+        if timed_out {
+            return Ok(ExecutionResult {
+                output: String::new(),
+                exit_code: -1,
+                execution_time: wall_time_limit,
+                memory_used: memory_used_kb,
+                error: Some("Timeout".to_string()),
+            });
+        }
+
+        if let (Some(limit_mb), Some(used_kb)) = (memory_limit_mb, memory_used_kb) {
+            if used_kb > limit_mb * 1024 {
+                return Ok(ExecutionResult {
+                    output: String::new(),
+                    exit_code: -1,
+                    execution_time,
+                    memory_used: memory_used_kb,
+                    error: Some("MemoryLimitExceeded".to_string()),
+                });
+            }
+        }
+
+        if libc::WIFSIGNALED(status) {
+            let sig = libc::WTERMSIG(status);
+            let error = if sig == libc::SIGXCPU {
+                "Timeout".to_string()
+            } else {
+                format!("Process terminated by signal {}", sig)
+            };
+            return Ok(ExecutionResult {
+                output,
+                exit_code: -1,
+                execution_time,
+                memory_used: memory_used_kb,
+                error: Some(error),
+            });
+        }
+
+        let exit_code = if libc::WIFEXITED(status) {
+            libc::WEXITSTATUS(status)
+        } else {
+            -1
+        };
+
+        let error = if exit_code != 0 {
+            Some(format!(
+                "Process exited abnormally, exit code: {}",
+                exit_code
+            ))
+        } else {
+            None
+        };
+
+        Ok(ExecutionResult {
+            output,
+            exit_code,
+            execution_time,
+            memory_used: memory_used_kb,
+            error,
+        })
+    }
+
+    /// 尝试在 cgroup v2 下为本次运行建立一个临时子 cgroup；
+    /// cgroup v2 未挂载或没有权限时返回 `None`，调用方回退到 getrusage
+    fn try_create_cgroup(run_id: &uuid::Uuid) -> Option<std::path::PathBuf> {
+        let base = Path::new("/sys/fs/cgroup/cpkit");
+        fs::create_dir_all(base).ok()?;
+        let dir = base.join(run_id.to_string());
+        fs::create_dir(&dir).ok()?;
+        Some(dir)
+    }
+
+    /// 读取 cgroup v2 `memory.peak`（字节），转换为 KB
+    fn read_cgroup_memory_peak_kb(dir: &Path) -> Option<u64> {
+        let raw = fs::read_to_string(dir.join("memory.peak")).ok()?;
+        raw.trim().parse::<u64>().ok().map(|bytes| bytes / 1024)
+    }
+
+    /// cgroup 不可用时用 `getrusage(RUSAGE_CHILDREN)` 兜底
+    /// （Linux 上 `ru_maxrss` 的单位已经是 KB）
+    fn read_rusage_children_maxrss_kb() -> Option<u64> {
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, &mut usage) };
+        if ret != 0 {
+            return None;
+        }
+        if usage.ru_maxrss > 0 {
+            Some(usage.ru_maxrss as u64)
+        } else {
+            None
+        }
+    }
+}
+
+/// 非 Linux 平台没有 setrlimit/cgroup 的等价物：退化为普通 spawn + 墙钟超时，
+/// 保证 GUI 在其他平台上依然可以运行测试，但不提供真正的 CPU/内存隔离
+#[cfg(not(target_os = "linux"))]
+mod passthrough {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    use std::time::Instant;
+
+    pub fn run_guarded(
+        executable: &Path,
+        input: &str,
+        wall_time_limit: Duration,
+        _memory_limit_mb: Option<u64>,
+    ) -> Result<ExecutionResult> {
+        let start = Instant::now();
+        let mut child = Command::new(executable)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Cannot start program")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = child.wait_with_output();
+            let _ = tx.send(result);
+        });
+
+        let output = match rx.recv_timeout(wall_time_limit) {
+            Ok(result) => result?,
+            Err(_) => {
+                return Ok(ExecutionResult {
+                    output: String::new(),
+                    exit_code: -1,
+                    execution_time: wall_time_limit,
+                    memory_used: None,
+                    error: Some("Timeout".to_string()),
+                });
+            }
+        };
+
+        let execution_time = start.elapsed();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        let error = if !output.status.success() {
+            Some(if stderr.is_empty() {
+                format!(
+                    "Process exited abnormally, exit code: {:?}",
+                    output.status.code()
+                )
+            } else {
+                stderr
+            })
+        } else {
+            None
+        };
+
+        Ok(ExecutionResult {
+            output: stdout,
+            exit_code: output.status.code().unwrap_or(-1),
+            execution_time,
+            memory_used: None,
+            error,
+        })
+    }
+}
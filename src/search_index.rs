@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// 可插拔的 embedding 后端：把一段文本映射成定长向量。
+/// 默认实现 [`HashingEmbedder`] 不依赖任何外部模型，离线即可用；
+/// 以后接入真正的本地模型时只需实现这个 trait 并替换默认后端。
+pub trait EmbeddingBackend: Send + Sync {
+    fn embed(&self, text: &str) -> Vec<f32>;
+    #[allow(dead_code)]
+    fn dim(&self) -> usize;
+}
+
+/// 轻量级默认后端：把文本按单词哈希进一个固定维度的词袋向量再做 L2 归一化，
+/// 使余弦相似度退化成点积。语义质量远不如真正的神经网络 embedding，
+/// 但不需要任何模型文件或推理运行时，适合作为离线场景下的开箱默认值。
+pub struct HashingEmbedder {
+    dim: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new(dim: usize) -> Self {
+        Self { dim }
+    }
+
+    /// FNV-1a：足够均匀、无需额外依赖
+    fn hash_token(token: &str) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in token.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl EmbeddingBackend for HashingEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dim];
+        for token in text.to_lowercase().split_whitespace() {
+            let idx = (Self::hash_token(token) as usize) % self.dim;
+            vector[idx] += 1.0;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in vector.iter_mut() {
+                *v /= norm;
+            }
+        }
+        vector
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedIndex {
+    vectors: HashMap<Uuid, Vec<f32>>,
+}
+
+/// 存量问题的语义索引：id -> 向量，随 `ProblemStore` 一起持久化在磁盘上
+pub struct SearchIndex {
+    backend: Box<dyn EmbeddingBackend>,
+    vectors: HashMap<Uuid, Vec<f32>>,
+    dirty: bool,
+}
+
+impl SearchIndex {
+    /// 索引 sidecar 文件在数据目录下的路径
+    pub fn index_path(data_dir: &Path) -> PathBuf {
+        data_dir.join("embeddings.json")
+    }
+
+    /// 加载已有索引；文件不存在或解析失败时回退为空索引，不阻塞正常使用
+    pub fn load(data_dir: &Path, backend: Box<dyn EmbeddingBackend>) -> Self {
+        let vectors = std::fs::read_to_string(Self::index_path(data_dir))
+            .ok()
+            .and_then(|json| serde_json::from_str::<PersistedIndex>(&json).ok())
+            .map(|persisted| persisted.vectors)
+            .unwrap_or_default();
+
+        Self {
+            backend,
+            vectors,
+            dirty: false,
+        }
+    }
+
+    /// 索引有变更时才落盘，避免后台轮询里无意义的重复写文件
+    pub fn save(&mut self, data_dir: &Path) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(data_dir)?;
+        let persisted = PersistedIndex {
+            vectors: self.vectors.clone(),
+        };
+        let json = serde_json::to_string_pretty(&persisted)?;
+        std::fs::write(Self::index_path(data_dir), json).context("写入语义索引失败")?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.vectors.contains_key(&id)
+    }
+
+    /// 嵌入一道题目的标题/分组文本并存入索引（新题到达时增量调用）
+    pub fn upsert(&mut self, id: Uuid, text: &str) {
+        let vector = self.backend.embed(text);
+        self.vectors.insert(id, vector);
+        self.dirty = true;
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, id: Uuid) {
+        if self.vectors.remove(&id).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// 按余弦相似度给存量问题排序，返回 (id, 相似度) 列表，最多 `top_k` 条
+    pub fn search(&self, query: &str, top_k: usize) -> Vec<(Uuid, f32)> {
+        let query_vector = self.backend.embed(query);
+
+        let mut scored: Vec<(Uuid, f32)> = self
+            .vectors
+            .iter()
+            .map(|(id, vector)| (*id, Self::cosine_similarity(&query_vector, vector)))
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(top_k);
+        scored
+    }
+
+    fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+        let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm_a == 0.0 || norm_b == 0.0 {
+            0.0
+        } else {
+            dot / (norm_a * norm_b)
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use crate::models::{Problem, TestCase};
+use crate::models::{Problem, RunHistoryEntry, RunStats, TestCase};
 use anyhow::Result;
+use chrono::Utc;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -10,6 +11,11 @@ pub struct ProblemStore {
     problems: HashMap<Uuid, Problem>,
     current_problem: Option<Uuid>,
     data_dir: PathBuf,
+    /// 整场比赛批量导入时，为每道题生成 stub 源文件用的文件名模板
+    /// （支持 `{index}`/`{index:02}`/`{name}`/`{slug}` 占位符）
+    stub_template: String,
+    /// stub 源文件的落地目录；为 `None` 时使用当前工作目录
+    stub_dir: Option<PathBuf>,
 }
 
 impl ProblemStore {
@@ -22,6 +28,8 @@ impl ProblemStore {
             problems: HashMap::new(),
             current_problem: None,
             data_dir,
+            stub_template: "{index:02}_{slug}".to_string(),
+            stub_dir: None,
         };
 
         // 从磁盘加载已保存的问题
@@ -30,6 +38,11 @@ impl ProblemStore {
         Ok(store)
     }
 
+    /// 数据存储目录，供语义索引等 sidecar 文件复用同一个目录
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
     /// 获取数据存储目录
     fn get_data_dir() -> Result<PathBuf> {
         let mut path = dirs::data_local_dir()
@@ -59,6 +72,63 @@ impl ProblemStore {
             .and_then(|id| self.problems.get_mut(&id))
     }
 
+    /// 按 id 获取任意问题（可变），用于跨问题批量操作
+    pub fn get_problem_mut(&mut self, id: Uuid) -> Option<&mut Problem> {
+        self.problems.get_mut(&id)
+    }
+
+    /// 切换当前问题（用于比赛边栏点击切题），id 不存在时返回 false
+    pub fn set_current_problem(&mut self, id: Uuid) -> bool {
+        if self.problems.contains_key(&id) {
+            self.current_problem = Some(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 按 `group` 字段列出属于同一场比赛的题目，顺序与 Competitive Companion 推送时一致
+    pub fn get_problems_by_group(&self, group: &str) -> Vec<&Problem> {
+        let mut problems: Vec<_> = self
+            .problems
+            .values()
+            .filter(|p| p.group == group)
+            .collect();
+        problems.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        problems
+    }
+
+    pub fn stub_template(&self) -> &str {
+        &self.stub_template
+    }
+
+    pub fn set_stub_template(&mut self, template: String) {
+        self.stub_template = template;
+    }
+
+    pub fn stub_dir(&self) -> Option<&Path> {
+        self.stub_dir.as_deref()
+    }
+
+    pub fn set_stub_dir(&mut self, dir: Option<PathBuf>) {
+        self.stub_dir = dir;
+    }
+
+    /// 根据模板和题目的序号/名称生成 stub 文件名（不含扩展名）；
+    /// 支持 `{index}`、`{index:02}`、`{name}`、`{slug}` 占位符
+    pub fn render_stub_filename(template: &str, index: usize, name: &str) -> String {
+        let slug: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+
+        template
+            .replace("{index:02}", &format!("{:02}", index))
+            .replace("{index}", &index.to_string())
+            .replace("{name}", name)
+            .replace("{slug}", &slug)
+    }
+
     /// 获取所有问题
     pub fn get_all_problems(&self) -> Vec<&Problem> {
         let mut problems: Vec<_> = self.problems.values().collect();
@@ -225,4 +295,48 @@ impl ProblemStore {
 
         Ok(())
     }
+
+    /// 获取源文件对应的判题历史保存路径
+    /// 例如: /path/to/solution.cpp -> /path/to/.cpkit/solution.cpp.history.json
+    fn get_source_file_history_path(source_file: &Path) -> Result<PathBuf> {
+        let tests_path = Self::get_source_file_tests_path(source_file)?;
+        Ok(tests_path.with_extension("history.json"))
+    }
+
+    /// 加载源文件对应的判题历史；文件不存在时返回空列表
+    pub fn load_run_history(source_file: &Path) -> Result<Vec<RunHistoryEntry>> {
+        let history_path = Self::get_source_file_history_path(source_file)?;
+
+        if !history_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = fs::read_to_string(&history_path)?;
+        let history: Vec<RunHistoryEntry> = serde_json::from_str(&json)?;
+        Ok(history)
+    }
+
+    /// 把一次判题运行的结果追加进源文件对应的历史记录
+    pub fn record_run(source_file: &Path, stats: &RunStats, compiler: &str) -> Result<()> {
+        let history_path = Self::get_source_file_history_path(source_file)?;
+
+        let mut history = Self::load_run_history(source_file)?;
+        history.push(RunHistoryEntry {
+            timestamp: Utc::now(),
+            compiler: compiler.to_string(),
+            stats: stats.clone(),
+        });
+
+        let json = serde_json::to_string_pretty(&history)?;
+        fs::write(&history_path, json)?;
+
+        if history_path.exists() {
+            let file_size = fs::metadata(&history_path)?.len();
+            tracing::info!("历史记录写入成功，大小: {} bytes", file_size);
+        } else {
+            tracing::error!("警告：历史记录文件写入后不存在！");
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,192 @@
+use anyhow::{Context, Result};
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// `Color32` 本身不参与序列化，这里退化成一个纯 (r, g, b, a) 元组，
+/// 只在读写配置文件和构造 `Color32` 的边界处转换
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rgba(pub u8, pub u8, pub u8, pub u8);
+
+impl Rgba {
+    const fn opaque(r: u8, g: u8, b: u8) -> Self {
+        Self(r, g, b, 255)
+    }
+
+    pub fn to_color32(self) -> Color32 {
+        Color32::from_rgba_unmultiplied(self.0, self.1, self.2, self.3)
+    }
+}
+
+/// `TestPanel`/`render_test_case` 里卡片、输入框、文字与按钮的颜色和主要字号，替代原先散落在各处的
+/// `Color32::from_rgb(...)` 字面量；随 `data_dir` 一起持久化，启动时加载，
+/// 并可通过 appearance 窗口实时编辑、在内置的 dark/light 预设间切换。
+/// 判题状态标签的颜色仍取自 `TestStatus::color()`，diff 行、粘贴按钮等处的小号文字仍用固定字号。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Appearance {
+    pub card_background: Rgba,
+    pub card_border: Rgba,
+    pub input_background: Rgba,
+    pub input_border: Rgba,
+    pub accepted: Rgba,
+    pub rejected: Rgba,
+    pub error_background: Rgba,
+    pub error_border: Rgba,
+    pub warning: Rgba,
+    /// 高亮态控件（如 watch 开关开启时）的强调色
+    pub accent: Rgba,
+    pub primary_text: Rgba,
+    pub muted_text: Rgba,
+    pub heading_size: f32,
+    pub body_size: f32,
+    pub small_size: f32,
+}
+
+impl Appearance {
+    /// 内置深色预设，和重构前散落各处的硬编码颜色保持一致，作为默认值
+    pub fn dark() -> Self {
+        Self {
+            card_background: Rgba::opaque(30, 30, 30),
+            card_border: Rgba::opaque(60, 60, 60),
+            input_background: Rgba::opaque(20, 20, 20),
+            input_border: Rgba::opaque(50, 50, 50),
+            accepted: Rgba::opaque(0, 200, 0),
+            rejected: Rgba::opaque(220, 80, 80),
+            error_background: Rgba::opaque(50, 20, 20),
+            error_border: Rgba::opaque(150, 0, 0),
+            warning: Rgba::opaque(230, 200, 0),
+            accent: Rgba::opaque(100, 200, 255),
+            primary_text: Rgba::opaque(255, 255, 255),
+            muted_text: Rgba::opaque(160, 160, 160),
+            heading_size: 14.0,
+            body_size: 13.0,
+            small_size: 11.0,
+        }
+    }
+
+    /// 内置浅色预设，供对比度敏感的用户切换
+    pub fn light() -> Self {
+        Self {
+            card_background: Rgba::opaque(245, 245, 245),
+            card_border: Rgba::opaque(200, 200, 200),
+            input_background: Rgba::opaque(255, 255, 255),
+            input_border: Rgba::opaque(210, 210, 210),
+            accepted: Rgba::opaque(0, 140, 0),
+            rejected: Rgba::opaque(190, 40, 40),
+            error_background: Rgba::opaque(255, 230, 230),
+            error_border: Rgba::opaque(200, 60, 60),
+            warning: Rgba::opaque(160, 120, 0),
+            accent: Rgba::opaque(20, 120, 200),
+            primary_text: Rgba::opaque(20, 20, 20),
+            muted_text: Rgba::opaque(100, 100, 100),
+            heading_size: 14.0,
+            body_size: 13.0,
+            small_size: 11.0,
+        }
+    }
+
+    fn config_path(data_dir: &Path) -> PathBuf {
+        data_dir.join("appearance.json")
+    }
+
+    /// 从 `data_dir` 加载外观配置；文件不存在或损坏时退回内置的 dark 预设
+    pub fn load(data_dir: &Path) -> Self {
+        std::fs::read_to_string(Self::config_path(data_dir))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(Self::dark)
+    }
+
+    /// 把当前外观配置写回 `data_dir`
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(data_dir)?;
+        let json = serde_json::to_string_pretty(self).context("序列化外观配置失败")?;
+        std::fs::write(Self::config_path(data_dir), json).context("写入外观配置失败")?;
+        Ok(())
+    }
+
+    /// 渲染外观设置窗口：预设切换 + 逐项颜色/字号编辑；返回本帧内配置是否被改动，
+    /// 供调用方决定是否需要 `save()`
+    pub fn window(&mut self, ctx: &egui::Context, open: &mut bool) -> bool {
+        let mut changed = false;
+
+        egui::Window::new("🎨 Appearance").open(open).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Dark preset").clicked() {
+                    *self = Self::dark();
+                    changed = true;
+                }
+                if ui.button("Light preset").clicked() {
+                    *self = Self::light();
+                    changed = true;
+                }
+            });
+
+            ui.separator();
+
+            macro_rules! color_row {
+                ($label:expr, $field:ident) => {
+                    ui.horizontal(|ui| {
+                        ui.label($label);
+                        let mut color = self.$field.to_color32();
+                        if ui.color_edit_button_srgba(&mut color).changed() {
+                            self.$field = Rgba(color.r(), color.g(), color.b(), color.a());
+                            changed = true;
+                        }
+                    });
+                };
+            }
+
+            color_row!("Card background", card_background);
+            color_row!("Card border", card_border);
+            color_row!("Input box background", input_background);
+            color_row!("Input box border", input_border);
+            color_row!("Accepted", accepted);
+            color_row!("Rejected", rejected);
+            color_row!("Error background", error_background);
+            color_row!("Error border", error_border);
+            color_row!("Warning", warning);
+            color_row!("Accent", accent);
+            color_row!("Primary text", primary_text);
+            color_row!("Muted text", muted_text);
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Heading size");
+                if ui
+                    .add(egui::DragValue::new(&mut self.heading_size).clamp_range(8.0..=32.0))
+                    .changed()
+                {
+                    changed = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Body size");
+                if ui
+                    .add(egui::DragValue::new(&mut self.body_size).clamp_range(8.0..=32.0))
+                    .changed()
+                {
+                    changed = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Small text size");
+                if ui
+                    .add(egui::DragValue::new(&mut self.small_size).clamp_range(6.0..=24.0))
+                    .changed()
+                {
+                    changed = true;
+                }
+            });
+        });
+
+        changed
+    }
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
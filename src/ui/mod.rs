@@ -0,0 +1,6 @@
+pub mod appearance;
+pub mod test_panel;
+pub mod toolbar;
+
+pub use test_panel::TestPanel;
+pub use toolbar::Toolbar;
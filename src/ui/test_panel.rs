@@ -1,8 +1,86 @@
-use crate::models::{TestCase, TestStatus};
+use crate::models::{CheckerMode, NormalizationConfig, TestCase, TestStatus};
+use crate::ui::appearance::Appearance;
 use arboard::Clipboard;
-use egui::{Color32, RichText, ScrollArea, TextEdit, Ui};
+use egui::{RichText, ScrollArea, TextEdit, Ui};
 use uuid::Uuid;
 
+/// LCS 行级 diff 里的一条操作
+#[derive(Debug, Clone)]
+enum DiffOp {
+    /// 期望和实际在这一行"配上了对"；`ws_mismatch` 标记两边是否仅有行尾空白/换行符不同
+    Equal {
+        expected: String,
+        actual: String,
+        ws_mismatch: bool,
+    },
+    /// 仅存在于期望输出
+    Removed(String),
+    /// 仅存在于实际输出
+    Added(String),
+}
+
+/// DP 表行数上限：超过这个规模就放弃 LCS，退化为纯文本展示，避免 O(m*n) 内存/时间爆炸
+const MAX_DIFF_LINES: usize = 5000;
+
+/// 对期望/实际输出做 LCS 行级 diff，返回 `None` 表示超出 [`MAX_DIFF_LINES`]、应退化为纯文本。
+///
+/// 用 `split('\n')` 而非 `lines()` 切分：这样末尾是否带结尾换行符的差异，
+/// 会体现为多出来的最后一条空行，从而也能被当成一条 diff 展示出来。
+/// 配对相等性用去掉行尾空白后的文本判断，这样仅有行尾空白/换行符不同的行依然能配成一对，
+/// 同时记录下来标成 whitespace-only mismatch，供调用方据此降级成 Presentation Error。
+fn diff_lines(expected: &str, actual: &str) -> Option<Vec<DiffOp>> {
+    let exp_lines: Vec<&str> = expected.split('\n').collect();
+    let act_lines: Vec<&str> = actual.split('\n').collect();
+    let (m, n) = (exp_lines.len(), act_lines.len());
+
+    if m > MAX_DIFF_LINES || n > MAX_DIFF_LINES {
+        return None;
+    }
+
+    let lines_match = |a: &str, b: &str| a.trim_end() == b.trim_end();
+
+    let mut dp = vec![vec![0usize; n + 1]; m + 1];
+    for i in (0..m).rev() {
+        for j in (0..n).rev() {
+            dp[i][j] = if lines_match(exp_lines[i], act_lines[j]) {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(m + n);
+    let (mut i, mut j) = (0, 0);
+    while i < m && j < n {
+        if lines_match(exp_lines[i], act_lines[j]) {
+            ops.push(DiffOp::Equal {
+                expected: exp_lines[i].to_string(),
+                actual: act_lines[j].to_string(),
+                ws_mismatch: exp_lines[i] != act_lines[j],
+            });
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            ops.push(DiffOp::Removed(exp_lines[i].to_string()));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(act_lines[j].to_string()));
+            j += 1;
+        }
+    }
+    while i < m {
+        ops.push(DiffOp::Removed(exp_lines[i].to_string()));
+        i += 1;
+    }
+    while j < n {
+        ops.push(DiffOp::Added(act_lines[j].to_string()));
+        j += 1;
+    }
+
+    Some(ops)
+}
+
 /// 测试面板
 pub struct TestPanel {
     collapsed_tests: std::collections::HashSet<Uuid>,
@@ -10,6 +88,10 @@ pub struct TestPanel {
     input_heights: std::collections::HashMap<Uuid, f32>, // 每个测试的输入框高度
     expected_heights: std::collections::HashMap<Uuid, f32>, // 每个测试的期望输出框高度
     last_test_status: std::collections::HashMap<Uuid, TestStatus>, // 记录上次的测试状态
+    normalization: NormalizationConfig,                   // 输出规范化配置
+    show_normalization_settings: bool,
+    /// 处于 Split（左右分栏）diff 视图的测试点；不在集合里的默认用 Unified 视图
+    split_diff_view: std::collections::HashSet<Uuid>,
 }
 
 impl Default for TestPanel {
@@ -20,6 +102,9 @@ impl Default for TestPanel {
             input_heights: std::collections::HashMap::new(),
             expected_heights: std::collections::HashMap::new(),
             last_test_status: std::collections::HashMap::new(),
+            split_diff_view: std::collections::HashSet::new(),
+            normalization: NormalizationConfig::default(),
+            show_normalization_settings: false,
         }
     }
 }
@@ -30,12 +115,30 @@ impl TestPanel {
     }
 
     /// 渲染测试面板
+    ///
+    /// `watch_enabled` 是 app 里那个单一的 watch 开关（与 Toolbar 上的 "👁 Watch" 共享同一份状态）；
+    /// `current_checker` 同理是 Toolbar 上那个全局 checker 选择（与 `Problem::checker` 双向同步）；
+    /// `stress_*` 同理镜像 Toolbar 上那套 generator/brute 对拍状态（与 `Problem::stress_generator`/
+    /// `stress_brute` 双向同步），点击 `on_toggle_stress` 时由调用方负责启动/停止对拍循环；
+    /// 这里只是把它们作为另一个入口暴露出来，方便在盯着测试列表时就地调整，不单独维护第二份状态。
+    /// `appearance` 是当前外观配置（除状态标签颜色和少数固定字号外，颜色/字号都从这里读取），点击 🎨 时把 `open_appearance_window`
+    /// 置为 true，由调用方负责渲染 [`Appearance::window`] 并在改动后保存。
+    #[allow(clippy::too_many_arguments)]
     pub fn ui(
         &mut self,
         ui: &mut Ui,
         tests: &mut Vec<TestCase>,
         on_delete_test: &mut Option<Uuid>,
         on_add_test: bool,
+        watch_enabled: &mut bool,
+        current_checker: &mut CheckerMode,
+        stress_configured: bool,
+        stress_running: bool,
+        stress_passed: u32,
+        stress_elapsed_secs: f32,
+        on_toggle_stress: &mut bool,
+        appearance: &Appearance,
+        open_appearance_window: &mut bool,
     ) {
         // 重置运行测试ID
         self.run_test_id = None;
@@ -52,12 +155,116 @@ impl TestPanel {
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.label(
                         RichText::new(format!("{} tests", tests.len()))
-                            .size(12.0)
-                            .color(Color32::GRAY),
+                            .size(appearance.small_size)
+                            .color(appearance.muted_text.to_color32()),
+                    );
+                    if ui
+                        .button(RichText::new("⚙").size(appearance.body_size))
+                        .on_hover_text("Output normalization settings")
+                        .clicked()
+                    {
+                        self.show_normalization_settings = !self.show_normalization_settings;
+                    }
+                    if ui
+                        .button(RichText::new("🎨").size(appearance.body_size))
+                        .on_hover_text("Appearance settings")
+                        .clicked()
+                    {
+                        *open_appearance_window = true;
+                    }
+                    let watch_color = if *watch_enabled {
+                        appearance.accent.to_color32()
+                    } else {
+                        appearance.muted_text.to_color32()
+                    };
+                    if ui
+                        .button(RichText::new("👁").size(appearance.body_size).color(watch_color))
+                        .on_hover_text("Watch: auto re-run all tests when the source file is saved")
+                        .clicked()
+                    {
+                        *watch_enabled = !*watch_enabled;
+                    }
+
+                    // 全局 checker 选择（与 Toolbar 共享同一份状态）
+                    egui::ComboBox::from_id_source("test_panel_checker_selector")
+                        .selected_text(current_checker.label())
+                        .width(70.0)
+                        .show_ui(ui, |ui| {
+                            for label in CheckerMode::all_labels() {
+                                let selected = current_checker.label() == *label;
+                                if ui.selectable_label(selected, *label).clicked() && !selected {
+                                    *current_checker = match *label {
+                                        "Token" => CheckerMode::Token,
+                                        "Float" => CheckerMode::Float {
+                                            abs_eps: 1e-6,
+                                            rel_eps: 1e-6,
+                                        },
+                                        "External" => CheckerMode::External {
+                                            program: String::new(),
+                                        },
+                                        _ => CheckerMode::Exact,
+                                    };
+                                }
+                            }
+                        });
+                    ui.label(
+                        RichText::new("Checker:")
+                            .size(appearance.small_size)
+                            .color(appearance.muted_text.to_color32()),
                     );
+
+                    if stress_running {
+                        ui.label(
+                            RichText::new(format!("{} iters, {:.1}s", stress_passed, stress_elapsed_secs))
+                                .size(appearance.small_size)
+                                .color(appearance.muted_text.to_color32()),
+                        );
+                        if ui
+                            .button(
+                                RichText::new("⏹")
+                                    .size(appearance.body_size)
+                                    .color(appearance.rejected.to_color32()),
+                            )
+                            .on_hover_text("Stop stress test")
+                            .clicked()
+                        {
+                            *on_toggle_stress = true;
+                        }
+                    } else {
+                        ui.add_enabled_ui(stress_configured, |ui| {
+                            if ui
+                                .button(RichText::new("🎲 Stress").size(appearance.body_size))
+                                .on_hover_text(
+                                    "Run generator vs. brute-force until a counterexample is found",
+                                )
+                                .clicked()
+                            {
+                                *on_toggle_stress = true;
+                            }
+                        });
+                    }
                 });
             });
 
+            if let CheckerMode::External { program } = current_checker {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new("Checker binary:")
+                            .size(appearance.small_size)
+                            .color(appearance.muted_text.to_color32()),
+                    );
+                    ui.add(
+                        TextEdit::singleline(program)
+                            .desired_width(ui.available_width())
+                            .hint_text("Path to testlib-style checker"),
+                    );
+                });
+            }
+
+            if self.show_normalization_settings {
+                self.render_normalization_settings(ui, appearance);
+            }
+
             ui.separator();
 
             if tests.is_empty() {
@@ -65,14 +272,14 @@ impl TestPanel {
                     ui.add_space(30.0);
                     ui.label(
                         RichText::new("No Test Cases")
-                            .size(14.0)
-                            .color(Color32::GRAY),
+                            .size(appearance.heading_size)
+                            .color(appearance.muted_text.to_color32()),
                     );
                     ui.add_space(10.0);
                     ui.label(
                         RichText::new("Click '➕ Add Test' button above to add custom test cases")
-                            .size(11.0)
-                            .color(Color32::DARK_GRAY),
+                            .size(appearance.small_size)
+                            .color(appearance.muted_text.to_color32()),
                     );
                 });
             } else {
@@ -97,13 +304,49 @@ impl TestPanel {
                                 self.last_test_status.insert(test.id, test.status);
                             }
 
-                            self.render_test_case(ui, test, i, on_delete_test);
+                            self.render_test_case(ui, test, i, on_delete_test, appearance);
                         }
                     });
             }
         });
     }
 
+    /// 渲染规范化规则编辑器
+    fn render_normalization_settings(&mut self, ui: &mut Ui, appearance: &Appearance) {
+        egui::Frame::none()
+            .fill(appearance.input_background.to_color32())
+            .inner_margin(8.0)
+            .rounding(4.0)
+            .show(ui, |ui| {
+                ui.label(
+                    RichText::new("Normalization regex substitutions (pattern => replacement)")
+                        .size(appearance.small_size)
+                        .color(appearance.primary_text.to_color32()),
+                );
+                let mut remove_at = None;
+                for (i, (pattern, replacement)) in
+                    self.normalization.regex_substitutions.iter_mut().enumerate()
+                {
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(pattern).desired_width(150.0));
+                        ui.label("=>");
+                        ui.add(TextEdit::singleline(replacement).desired_width(150.0));
+                        if ui.small_button("🗑").clicked() {
+                            remove_at = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove_at {
+                    self.normalization.regex_substitutions.remove(i);
+                }
+                if ui.small_button("➕ Add rule").clicked() {
+                    self.normalization
+                        .regex_substitutions
+                        .push((String::new(), String::new()));
+                }
+            });
+    }
+
     /// 渲染单个测试用例
     fn render_test_case(
         &mut self,
@@ -111,12 +354,13 @@ impl TestPanel {
         test: &mut TestCase,
         index: usize,
         on_delete_test: &mut Option<Uuid>,
+        appearance: &Appearance,
     ) {
         let is_collapsed = self.collapsed_tests.contains(&test.id);
 
         let frame = egui::Frame::none()
-            .fill(Color32::from_rgb(30, 30, 30))
-            .stroke(egui::Stroke::new(1.0, Color32::from_rgb(60, 60, 60)))
+            .fill(appearance.card_background.to_color32())
+            .stroke(egui::Stroke::new(1.0, appearance.card_border.to_color32()))
             .inner_margin(10.0)
             .outer_margin(egui::Margin::symmetric(0.0, 4.0))
             .rounding(6.0);
@@ -137,16 +381,16 @@ impl TestPanel {
                 // Status icon and title
                 ui.label(
                     RichText::new(format!("Test #{}", index))
-                        .size(13.0)
+                        .size(appearance.body_size)
                         .strong()
-                        .color(Color32::WHITE),
+                        .color(appearance.primary_text.to_color32()),
                 );
 
                 // Status text
                 if test.status != TestStatus::Pending {
                     ui.label(
                         RichText::new(format!("- {}", test.status.text()))
-                            .size(12.0)
+                            .size(appearance.body_size)
                             .color(test.status.color()),
                     );
                 }
@@ -156,15 +400,15 @@ impl TestPanel {
                     ui.separator();
                     ui.label(
                         RichText::new(format!("⏱ {:.0}ms", time.as_millis()))
-                            .size(11.0)
-                            .color(Color32::GRAY),
+                            .size(appearance.small_size)
+                            .color(appearance.muted_text.to_color32()),
                     );
                 }
 
                 // Right side buttons
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui
-                        .button(RichText::new("🗑").color(Color32::from_rgb(200, 0, 0)))
+                        .button(RichText::new("🗑").color(appearance.rejected.to_color32()))
                         .on_hover_text("Delete this test")
                         .clicked()
                     {
@@ -173,15 +417,58 @@ impl TestPanel {
 
                     // 运行按钮
                     if ui
-                        .button(RichText::new("▶").color(Color32::from_rgb(0, 200, 0)))
+                        .button(RichText::new("▶").color(appearance.accepted.to_color32()))
                         .on_hover_text("Run this test")
                         .clicked()
                     {
                         self.run_test_id = Some(test.id);
                     }
+
+                    // 本测试点的 checker 覆盖（special judge），默认沿用全局 checker
+                    let current_label = test.checker.as_ref().map(|c| c.label()).unwrap_or("Default");
+                    egui::ComboBox::from_id_source(("test_checker", test.id))
+                        .selected_text(current_label)
+                        .width(70.0)
+                        .show_ui(ui, |ui| {
+                            let selected = test.checker.is_none();
+                            if ui.selectable_label(selected, "Default").clicked() && !selected {
+                                test.checker = None;
+                            }
+                            for label in CheckerMode::all_labels() {
+                                let selected = current_label == *label;
+                                if ui.selectable_label(selected, *label).clicked() && !selected {
+                                    test.checker = Some(match *label {
+                                        "Token" => CheckerMode::Token,
+                                        "Float" => CheckerMode::Float {
+                                            abs_eps: 1e-6,
+                                            rel_eps: 1e-6,
+                                        },
+                                        "External" => CheckerMode::External {
+                                            program: String::new(),
+                                        },
+                                        _ => CheckerMode::Exact,
+                                    });
+                                }
+                            }
+                        });
                 });
             });
 
+            if let Some(CheckerMode::External { program }) = &mut test.checker {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new("Checker binary:")
+                            .size(appearance.small_size)
+                            .color(appearance.muted_text.to_color32()),
+                    );
+                    ui.add(
+                        TextEdit::singleline(program)
+                            .desired_width(ui.available_width())
+                            .hint_text("Path to testlib-style checker"),
+                    );
+                });
+            }
+
             // 只在未折叠时显示详细内容
             if !is_collapsed {
                 ui.add_space(6.0);
@@ -190,8 +477,8 @@ impl TestPanel {
                 ui.horizontal(|ui| {
                     ui.label(
                         RichText::new("Input:")
-                            .size(11.0)
-                            .color(Color32::LIGHT_GRAY),
+                            .size(appearance.small_size)
+                            .color(appearance.primary_text.to_color32()),
                     );
 
                     // 粘贴按钮
@@ -212,8 +499,8 @@ impl TestPanel {
                 let input_height = self.input_heights.entry(test.id).or_insert(100.0);
 
                 egui::Frame::none()
-                    .fill(Color32::from_rgb(20, 20, 20))
-                    .stroke(egui::Stroke::new(1.0, Color32::from_rgb(50, 50, 50)))
+                    .fill(appearance.input_background.to_color32())
+                    .stroke(egui::Stroke::new(1.0, appearance.input_border.to_color32()))
                     .inner_margin(6.0)
                     .rounding(3.0)
                     .show(ui, |ui| {
@@ -242,7 +529,7 @@ impl TestPanel {
                 ui.painter().hline(
                     ui.cursor().left()..=ui.cursor().right(),
                     ui.cursor().top() + 3.0,
-                    egui::Stroke::new(1.0, Color32::from_rgb(100, 100, 100)),
+                    egui::Stroke::new(1.0, appearance.input_border.to_color32()),
                 );
 
                 if resize_response.hovered() {
@@ -260,8 +547,8 @@ impl TestPanel {
                 ui.horizontal(|ui| {
                     ui.label(
                         RichText::new("Expected Output:")
-                            .size(11.0)
-                            .color(Color32::LIGHT_GRAY),
+                            .size(appearance.small_size)
+                            .color(appearance.primary_text.to_color32()),
                     );
 
                     // 粘贴按钮
@@ -282,8 +569,8 @@ impl TestPanel {
                 let expected_height = self.expected_heights.entry(test.id).or_insert(100.0);
 
                 egui::Frame::none()
-                    .fill(Color32::from_rgb(20, 20, 20))
-                    .stroke(egui::Stroke::new(1.0, Color32::from_rgb(50, 50, 50)))
+                    .fill(appearance.input_background.to_color32())
+                    .stroke(egui::Stroke::new(1.0, appearance.input_border.to_color32()))
                     .inner_margin(6.0)
                     .rounding(3.0)
                     .show(ui, |ui| {
@@ -312,7 +599,7 @@ impl TestPanel {
                 ui.painter().hline(
                     ui.cursor().left()..=ui.cursor().right(),
                     ui.cursor().top() + 3.0,
-                    egui::Stroke::new(1.0, Color32::from_rgb(100, 100, 100)),
+                    egui::Stroke::new(1.0, appearance.input_border.to_color32()),
                 );
 
                 if resize_response.hovered() {
@@ -325,47 +612,83 @@ impl TestPanel {
                 }
 
                 // Actual output (if any)
-                if let Some(actual_output) = &test.actual_output {
+                if let Some(actual) = test.actual_output.clone() {
                     ui.add_space(6.0);
 
-                    ui.label(
-                        RichText::new("Actual Output:")
-                            .size(11.0)
-                            .color(Color32::LIGHT_GRAY),
-                    );
+                    let is_split = self.split_diff_view.contains(&test.id);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new("Actual Output (diff vs Expected):")
+                                .size(appearance.small_size)
+                                .color(appearance.primary_text.to_color32()),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let toggle_label = if is_split { "Split" } else { "Unified" };
+                            if ui.small_button(toggle_label).clicked() {
+                                if is_split {
+                                    self.split_diff_view.remove(&test.id);
+                                } else {
+                                    self.split_diff_view.insert(test.id);
+                                }
+                            }
+                        });
+                    });
                     ui.add_space(2.0);
 
-                    let output_color = match test.status {
-                        TestStatus::Accepted => Color32::from_rgb(0, 50, 0),
-                        TestStatus::WrongAnswer => Color32::from_rgb(50, 0, 0),
-                        _ => Color32::from_rgb(20, 20, 20),
-                    };
+                    match diff_lines(&test.expected_output, &actual) {
+                        Some(ops) => {
+                            let has_real_diff = ops
+                                .iter()
+                                .any(|op| matches!(op, DiffOp::Added(_) | DiffOp::Removed(_)));
+                            let has_ws_mismatch = ops.iter().any(
+                                |op| matches!(op, DiffOp::Equal { ws_mismatch: true, .. }),
+                            );
+                            if test.status == TestStatus::Accepted
+                                && !has_real_diff
+                                && has_ws_mismatch
+                            {
+                                test.status = TestStatus::PresentationError;
+                            }
 
-                    egui::Frame::none()
-                        .fill(output_color)
-                        .stroke(egui::Stroke::new(
-                            1.0,
-                            match test.status {
-                                TestStatus::Accepted => Color32::from_rgb(0, 150, 0),
-                                TestStatus::WrongAnswer => Color32::from_rgb(150, 0, 0),
-                                _ => Color32::from_rgb(50, 50, 50),
-                            },
-                        ))
-                        .inner_margin(6.0)
-                        .rounding(3.0)
-                        .show(ui, |ui| {
-                            ScrollArea::vertical()
-                                .id_source(format!("actual_{}", test.id))
-                                .max_height(100.0)
+                            egui::Frame::none()
+                                .fill(appearance.input_background.to_color32())
+                                .stroke(egui::Stroke::new(1.0, appearance.input_border.to_color32()))
+                                .inner_margin(6.0)
+                                .rounding(3.0)
                                 .show(ui, |ui| {
-                                    ui.add(
-                                        TextEdit::multiline(&mut actual_output.as_str())
-                                            .desired_width(f32::INFINITY)
-                                            .font(egui::TextStyle::Monospace)
-                                            .interactive(false),
-                                    );
+                                    if is_split {
+                                        Self::render_split_diff(ui, test.id, &ops, appearance);
+                                    } else {
+                                        Self::render_unified_diff(ui, test.id, &ops, appearance);
+                                    }
                                 });
-                        });
+                        }
+                        None => {
+                            ui.label(
+                                RichText::new(format!(
+                                    "输出超过 {} 行，已跳过 diff 计算，直接显示原始文本：",
+                                    MAX_DIFF_LINES
+                                ))
+                                .size(10.0)
+                                .color(appearance.muted_text.to_color32()),
+                            );
+                            egui::Frame::none()
+                                .fill(appearance.input_background.to_color32())
+                                .stroke(egui::Stroke::new(1.0, appearance.input_border.to_color32()))
+                                .inner_margin(6.0)
+                                .rounding(3.0)
+                                .show(ui, |ui| {
+                                    ScrollArea::vertical()
+                                        .id_source(format!("actual_plain_{}", test.id))
+                                        .max_height(160.0)
+                                        .show(ui, |ui| {
+                                            ui.label(
+                                                RichText::new(&actual).monospace().size(11.0),
+                                            );
+                                        });
+                                });
+                        }
+                    }
                 }
 
                 // Error message (if any)
@@ -373,15 +696,15 @@ impl TestPanel {
                     ui.add_space(6.0);
 
                     egui::Frame::none()
-                        .fill(Color32::from_rgb(50, 20, 20))
-                        .stroke(egui::Stroke::new(1.0, Color32::from_rgb(150, 0, 0)))
+                        .fill(appearance.error_background.to_color32())
+                        .stroke(egui::Stroke::new(1.0, appearance.error_border.to_color32()))
                         .inner_margin(6.0)
                         .rounding(3.0)
                         .show(ui, |ui| {
                             ui.label(
                                 RichText::new(format!("❌ Error: {}", error))
                                     .size(11.0)
-                                    .color(Color32::from_rgb(255, 100, 100))
+                                    .color(appearance.rejected.to_color32())
                                     .monospace(),
                             );
                         });
@@ -390,6 +713,127 @@ impl TestPanel {
         });
     }
 
+    /// Unified 视图：一条条按 ` `/`-`/`+` 前缀顺序渲染；whitespace-only 的 Equal 行加下划线提示
+    fn render_unified_diff(ui: &mut Ui, test_id: Uuid, ops: &[DiffOp], appearance: &Appearance) {
+        ScrollArea::vertical()
+            .id_source(format!("actual_unified_{}", test_id))
+            .max_height(160.0)
+            .show(ui, |ui| {
+                for op in ops {
+                    let (prefix, text, color, underline) = match op {
+                        DiffOp::Equal {
+                            actual,
+                            ws_mismatch,
+                            ..
+                        } => (
+                            ' ',
+                            actual.clone(),
+                            if *ws_mismatch {
+                                appearance.warning.to_color32()
+                            } else {
+                                appearance.primary_text.to_color32()
+                            },
+                            *ws_mismatch,
+                        ),
+                        DiffOp::Removed(s) => ('-', s.clone(), appearance.rejected.to_color32(), false),
+                        DiffOp::Added(s) => ('+', s.clone(), appearance.accepted.to_color32(), false),
+                    };
+                    let mut rich = RichText::new(format!("{} {}", prefix, text))
+                        .monospace()
+                        .size(11.0)
+                        .color(color);
+                    if underline {
+                        rich = rich.underline();
+                    }
+                    ui.label(rich);
+                }
+            });
+    }
+
+    /// Split 视图：期望在左、实际在右，以 Equal 为锚点逐行对齐
+    /// （Removed 只占左列一行、Added 只占右列一行，对侧填空白占位，保持两列行数一致）
+    fn render_split_diff(ui: &mut Ui, test_id: Uuid, ops: &[DiffOp], appearance: &Appearance) {
+        ui.columns(2, |columns| {
+            let render_column = |ui: &mut Ui, side: &str| {
+                ScrollArea::vertical()
+                    .id_source(format!("actual_split_{}_{}", side, test_id))
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for op in ops {
+                            match (side, op) {
+                                (
+                                    "left",
+                                    DiffOp::Equal {
+                                        expected,
+                                        ws_mismatch,
+                                        ..
+                                    },
+                                ) => {
+                                    let color = if *ws_mismatch {
+                                        appearance.warning.to_color32()
+                                    } else {
+                                        appearance.primary_text.to_color32()
+                                    };
+                                    let mut rich = RichText::new(expected)
+                                        .monospace()
+                                        .size(11.0)
+                                        .color(color);
+                                    if *ws_mismatch {
+                                        rich = rich.underline();
+                                    }
+                                    ui.label(rich);
+                                }
+                                (
+                                    "right",
+                                    DiffOp::Equal {
+                                        actual,
+                                        ws_mismatch,
+                                        ..
+                                    },
+                                ) => {
+                                    let color = if *ws_mismatch {
+                                        appearance.warning.to_color32()
+                                    } else {
+                                        appearance.primary_text.to_color32()
+                                    };
+                                    let mut rich = RichText::new(actual)
+                                        .monospace()
+                                        .size(11.0)
+                                        .color(color);
+                                    if *ws_mismatch {
+                                        rich = rich.underline();
+                                    }
+                                    ui.label(rich);
+                                }
+                                ("left", DiffOp::Removed(s)) => {
+                                    ui.label(
+                                        RichText::new(s)
+                                            .monospace()
+                                            .size(11.0)
+                                            .color(appearance.rejected.to_color32()),
+                                    );
+                                }
+                                ("right", DiffOp::Added(s)) => {
+                                    ui.label(
+                                        RichText::new(s)
+                                            .monospace()
+                                            .size(11.0)
+                                            .color(appearance.accepted.to_color32()),
+                                    );
+                                }
+                                _ => {
+                                    // 对侧没有对应改动，用空行占位以保持两列行数一致
+                                    ui.label(RichText::new(" ").monospace().size(11.0));
+                                }
+                            }
+                        }
+                    });
+            };
+            render_column(&mut columns[0], "left");
+            render_column(&mut columns[1], "right");
+        });
+    }
+
     pub fn get_run_test_id(&self) -> Option<Uuid> {
         self.run_test_id
     }
@@ -1,11 +1,16 @@
-use crate::models::Language;
+use crate::backend::BackendKind;
+use crate::models::{CheckerMode, Language};
 use egui::{Button, Color32, RichText, Ui};
 
+/// 默认监听的文件模式
+pub const DEFAULT_WATCH_PATTERNS: &str = "*.cpp, *.rs, *.py, *.c, *.java";
+
 /// 工具栏面板
 pub struct Toolbar;
 
 impl Toolbar {
     /// 渲染工具栏
+    #[allow(clippy::too_many_arguments)]
     pub fn ui(
         ui: &mut Ui,
         current_language: &mut Language,
@@ -14,8 +19,27 @@ impl Toolbar {
         on_stop: &mut bool,
         on_add_test: &mut bool,
         on_clear_results: &mut bool,
+        on_run_all_problems: &mut bool,
         has_problem: bool,
         is_running: bool,
+        watch_enabled: &mut bool,
+        watch_patterns: &mut String,
+        current_checker: &mut CheckerMode,
+        time_limit_ms: &mut u64,
+        memory_limit_mb: &mut u64,
+        backend_kind: &mut BackendKind,
+        listen_port: &mut u16,
+        server_running: bool,
+        on_toggle_server: &mut bool,
+        on_import_dir: &mut bool,
+        parallel_workers: &mut usize,
+        stress_generator: &mut String,
+        stress_brute: &mut String,
+        stress_iterations: &mut u32,
+        stress_running: bool,
+        stress_passed: u32,
+        stress_elapsed_secs: f32,
+        on_toggle_stress: &mut bool,
     ) {
         ui.vertical(|ui| {
             // 第一行：标题和状态
@@ -63,8 +87,58 @@ impl Toolbar {
                         *source_file = path.display().to_string();
                     }
                 }
+
+                ui.separator();
+
+                ui.label("Time (ms):");
+                ui.add(egui::DragValue::new(time_limit_ms).clamp_range(100..=60000));
+
+                ui.label("Memory (MB):");
+                ui.add(egui::DragValue::new(memory_limit_mb).clamp_range(16..=4096));
+
+                ui.separator();
+
+                ui.label("Workers:").on_hover_text("Max concurrent test cases");
+                ui.add(egui::DragValue::new(parallel_workers).clamp_range(1..=64));
+
+                ui.separator();
+
+                ui.label("Checker:");
+                egui::ComboBox::from_id_source("checker_selector")
+                    .selected_text(current_checker.label())
+                    .show_ui(ui, |ui| {
+                        for label in CheckerMode::all_labels() {
+                            let selected = current_checker.label() == *label;
+                            if ui.selectable_label(selected, *label).clicked() && !selected {
+                                *current_checker = match *label {
+                                    "Token" => CheckerMode::Token,
+                                    "Float" => CheckerMode::Float {
+                                        abs_eps: 1e-6,
+                                        rel_eps: 1e-6,
+                                    },
+                                    "External" => CheckerMode::External {
+                                        program: String::new(),
+                                    },
+                                    _ => CheckerMode::Exact,
+                                };
+                            }
+                        }
+                    });
             });
 
+            // Checker 的附加参数（外部 checker 路径）
+            if let CheckerMode::External { program } = current_checker {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Checker binary:").size(11.0).color(Color32::GRAY));
+                    ui.add(
+                        egui::TextEdit::singleline(program)
+                            .desired_width(ui.available_width())
+                            .hint_text("Path to testlib-style checker"),
+                    );
+                });
+            }
+
             ui.add_space(4.0);
 
             // 第三行：操作按钮
@@ -119,6 +193,158 @@ impl Toolbar {
                         *on_clear_results = true;
                     }
                 });
+
+                ui.separator();
+
+                ui.add_enabled_ui(!is_running, |ui| {
+                    if ui
+                        .button("🗂 Run All Problems")
+                        .on_hover_text("Judge every stored problem as a regression pass")
+                        .clicked()
+                    {
+                        *on_run_all_problems = true;
+                    }
+                });
+
+                ui.separator();
+
+                // Watch 模式开关
+                ui.checkbox(watch_enabled, "👁 Watch")
+                    .on_hover_text("Auto recompile and rerun when the source file changes");
+            });
+
+            if *watch_enabled {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Patterns:").size(11.0).color(Color32::GRAY));
+                    ui.add(
+                        egui::TextEdit::singleline(watch_patterns)
+                            .desired_width(ui.available_width())
+                            .hint_text(DEFAULT_WATCH_PATTERNS),
+                    );
+                });
+            }
+
+            ui.add_space(4.0);
+
+            // 第四行：问题来源后端
+            ui.horizontal(|ui| {
+                ui.label("Backend:");
+                egui::ComboBox::from_id_source("backend_selector")
+                    .selected_text(backend_kind.label())
+                    .show_ui(ui, |ui| {
+                        for kind in BackendKind::all() {
+                            ui.selectable_value(backend_kind, *kind, kind.label());
+                        }
+                    });
+
+                match backend_kind {
+                    BackendKind::Network => {
+                        ui.label("Port:");
+                        ui.add_enabled(
+                            !server_running,
+                            egui::DragValue::new(listen_port).clamp_range(1024..=65535),
+                        );
+
+                        let (label, color) = if server_running {
+                            ("⏹ Stop Listener", Color32::from_rgb(200, 0, 0))
+                        } else {
+                            ("▶ Start Listener", Color32::from_rgb(0, 120, 212))
+                        };
+                        if ui
+                            .add(Button::new(RichText::new(label).color(Color32::WHITE)).fill(color))
+                            .on_hover_text("Receive problems pushed by the Competitive Companion browser extension")
+                            .clicked()
+                        {
+                            *on_toggle_server = true;
+                        }
+                    }
+                    BackendKind::Filesystem => {
+                        if ui
+                            .button("📂 Import Folder")
+                            .on_hover_text("Load input*/output* files from a directory as test cases")
+                            .clicked()
+                        {
+                            *on_import_dir = true;
+                        }
+                    }
+                }
+            });
+
+            ui.add_space(4.0);
+
+            // 第五行：压力测试（generator + brute force 对拍）
+            ui.horizontal(|ui| {
+                ui.label("Stress:");
+
+                ui.label(RichText::new("Gen:").size(11.0).color(Color32::GRAY));
+                ui.add(
+                    egui::TextEdit::singleline(stress_generator)
+                        .desired_width(120.0)
+                        .hint_text("generator path"),
+                );
+                if ui
+                    .small_button("📁")
+                    .on_hover_text("Browse generator...")
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        *stress_generator = path.display().to_string();
+                    }
+                }
+
+                ui.label(RichText::new("Brute:").size(11.0).color(Color32::GRAY));
+                ui.add(
+                    egui::TextEdit::singleline(stress_brute)
+                        .desired_width(120.0)
+                        .hint_text("brute-force path"),
+                );
+                if ui
+                    .small_button("📁")
+                    .on_hover_text("Browse brute-force solution...")
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        *stress_brute = path.display().to_string();
+                    }
+                }
+
+                ui.label("Iter:");
+                ui.add(egui::DragValue::new(stress_iterations).clamp_range(1..=1_000_000));
+
+                ui.add_enabled_ui(
+                    !stress_running
+                        && !is_running
+                        && !stress_generator.is_empty()
+                        && !stress_brute.is_empty(),
+                    |ui| {
+                        if ui
+                            .button("🧪 Stress")
+                            .on_hover_text("Run generator vs. brute-force until a counterexample is found")
+                            .clicked()
+                        {
+                            *on_toggle_stress = true;
+                        }
+                    },
+                );
+
+                ui.add_enabled_ui(stress_running, |ui| {
+                    if ui
+                        .button("⏹")
+                        .on_hover_text("Stop stress test")
+                        .clicked()
+                    {
+                        *on_toggle_stress = true;
+                    }
+                });
+
+                if stress_running {
+                    ui.label(
+                        RichText::new(format!("{} iters, {:.1}s", stress_passed, stress_elapsed_secs))
+                            .size(11.0)
+                            .color(Color32::LIGHT_BLUE),
+                    );
+                }
             });
         });
     }